@@ -0,0 +1,239 @@
+#![no_std]
+use soroban_sdk::{contract, contracttype, contractimpl, symbol_short, Address, Env, IntoVal, String, Val};
+
+// Token IDs are encoded the same way SoroSusu encodes them
+#[path = "../../../susu_math.rs"]
+pub mod math;
+
+// --- DATA STRUCTURES ---
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const TOKEN_TTL: u32 = 180 * DAY_IN_LEDGERS; // How long a receipt entry stays live after it is written
+const TOKEN_TTL_THRESHOLD: u32 = 150 * DAY_IN_LEDGERS;
+
+// Only the minter lives in instance storage; receipts and balances are persistent
+// entries of their own, so the instance entry does not grow with every mint
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    // The SoroSusu contract allowed to mint, burn, unlock and upgrade receipts
+    Minter,
+    Owner(u128),
+    Balance(Address),
+    Metadata(u128),
+    // Receipts become transferable once their circle completes
+    Unlocked(u128),
+    Badge(u128),
+}
+
+// Must match SoroSusu's NftMetadata field for field
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NftMetadata {
+    pub circle_id: u64,
+    pub slot: u32,
+    pub token: Address,
+    pub contribution_amount: i128,
+    pub joined_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenInfo {
+    pub owner: Address,
+    pub metadata: NftMetadata,
+    pub transferable: bool,
+    pub completed: bool, // Upgraded to a completion badge
+}
+
+// --- CONTRACT TRAIT ---
+
+pub trait SusuNftTrait {
+    // Bind the NFT to the SoroSusu contract that will manage it (the deployer signs as admin)
+    fn init(env: Env, admin: Address, minter: Address);
+
+    // Mint a participation receipt (SoroSusu only)
+    fn mint(env: Env, to: Address, token_id: u128, metadata: NftMetadata);
+
+    // Burn a receipt when its holder leaves the circle (SoroSusu only)
+    fn burn(env: Env, from: Address, token_id: u128);
+
+    // Allow a receipt to be transferred once its circle has completed (SoroSusu only)
+    fn unlock(env: Env, token_id: u128);
+
+    // Upgrade a receipt to a completion badge (SoroSusu only)
+    fn award_badge(env: Env, token_id: u128);
+
+    // Move a receipt to a member's recovered address, even while soulbound (SoroSusu only)
+    fn recover(env: Env, from: Address, to: Address, token_id: u128);
+
+    // Move an unlocked receipt to a new owner
+    fn transfer(env: Env, from: Address, to: Address, token_id: u128);
+
+    // Get the current holder of a receipt
+    fn owner_of(env: Env, token_id: u128) -> Address;
+
+    // Count the receipts an address holds
+    fn balance(env: Env, owner: Address) -> u32;
+
+    // Get everything known about a receipt
+    fn token_info(env: Env, token_id: u128) -> TokenInfo;
+
+    fn name(env: Env) -> String;
+
+    fn symbol(env: Env) -> String;
+}
+
+#[contract]
+pub struct SusuNft;
+
+#[contractimpl]
+impl SusuNftTrait for SusuNft {
+    fn init(env: Env, admin: Address, minter: Address) {
+        if env.storage().instance().has(&DataKey::Minter) {
+            panic!("Already initialized");
+        }
+        // Deploy and init in one transaction so no one else can bind the NFT first
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Minter, &minter);
+    }
+
+    fn mint(env: Env, to: Address, token_id: u128, metadata: NftMetadata) {
+        require_minter(&env);
+
+        if env.storage().persistent().has(&DataKey::Owner(token_id)) {
+            panic!("Token already minted");
+        }
+
+        set_entry(&env, &DataKey::Owner(token_id), &to);
+        set_entry(&env, &DataKey::Metadata(token_id), &metadata);
+        adjust_balance(&env, &to, 1);
+
+        env.events().publish((symbol_short!("mint"), to), token_id);
+    }
+
+    fn burn(env: Env, from: Address, token_id: u128) {
+        require_minter(&env);
+
+        let owner = Self::owner_of(env.clone(), token_id);
+        if owner != from {
+            panic!("Token is not owned by this address");
+        }
+
+        env.storage().persistent().remove(&DataKey::Owner(token_id));
+        env.storage().persistent().remove(&DataKey::Metadata(token_id));
+        env.storage().persistent().remove(&DataKey::Unlocked(token_id));
+        env.storage().persistent().remove(&DataKey::Badge(token_id));
+        adjust_balance(&env, &from, -1);
+
+        env.events().publish((symbol_short!("burn"), from), token_id);
+    }
+
+    fn unlock(env: Env, token_id: u128) {
+        require_minter(&env);
+        require_minted(&env, token_id);
+        set_entry(&env, &DataKey::Unlocked(token_id), &true);
+    }
+
+    fn award_badge(env: Env, token_id: u128) {
+        require_minter(&env);
+        require_minted(&env, token_id);
+        set_entry(&env, &DataKey::Badge(token_id), &true);
+
+        env.events().publish((symbol_short!("badge"), token_id), ());
+    }
+
+    fn recover(env: Env, from: Address, to: Address, token_id: u128) {
+        require_minter(&env);
+
+        let owner = Self::owner_of(env.clone(), token_id);
+        if owner != from {
+            panic!("Token is not owned by this address");
+        }
+
+        set_entry(&env, &DataKey::Owner(token_id), &to);
+        adjust_balance(&env, &from, -1);
+        adjust_balance(&env, &to, 1);
+
+        env.events().publish((symbol_short!("recover"), from, to), token_id);
+    }
+
+    fn transfer(env: Env, from: Address, to: Address, token_id: u128) {
+        from.require_auth();
+
+        let owner = Self::owner_of(env.clone(), token_id);
+        if owner != from {
+            panic!("Token is not owned by this address");
+        }
+
+        // Receipts are soulbound while the circle is running
+        if !env.storage().persistent().get(&DataKey::Unlocked(token_id)).unwrap_or(false) {
+            panic!("Token is soulbound while the circle is active");
+        }
+
+        set_entry(&env, &DataKey::Owner(token_id), &to);
+        adjust_balance(&env, &from, -1);
+        adjust_balance(&env, &to, 1);
+
+        env.events().publish((symbol_short!("transfer"), from, to), token_id);
+    }
+
+    fn owner_of(env: Env, token_id: u128) -> Address {
+        env.storage().persistent().get(&DataKey::Owner(token_id)).unwrap_or_else(|| panic!("Token does not exist"))
+    }
+
+    fn balance(env: Env, owner: Address) -> u32 {
+        env.storage().persistent().get(&DataKey::Balance(owner)).unwrap_or(0)
+    }
+
+    fn token_info(env: Env, token_id: u128) -> TokenInfo {
+        TokenInfo {
+            owner: Self::owner_of(env.clone(), token_id),
+            metadata: env.storage().persistent().get(&DataKey::Metadata(token_id)).unwrap_or_else(|| panic!("Token has no metadata")),
+            transferable: env.storage().persistent().get(&DataKey::Unlocked(token_id)).unwrap_or(false),
+            completed: env.storage().persistent().get(&DataKey::Badge(token_id)).unwrap_or(false),
+        }
+    }
+
+    fn name(env: Env) -> String {
+        String::from_str(&env, "SoroSusu Participation")
+    }
+
+    fn symbol(env: Env) -> String {
+        String::from_str(&env, "SUSU")
+    }
+}
+
+// --- INTERNAL HELPERS ---
+
+fn require_minter(env: &Env) {
+    let minter: Address = env.storage().instance().get(&DataKey::Minter).unwrap_or_else(|| panic!("Not initialized"));
+    minter.require_auth();
+}
+
+fn require_minted(env: &Env, token_id: u128) {
+    if !env.storage().persistent().has(&DataKey::Owner(token_id)) {
+        panic!("Token does not exist");
+    }
+}
+
+fn adjust_balance(env: &Env, owner: &Address, delta: i32) {
+    let key = DataKey::Balance(owner.clone());
+    let balance: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+    let balance = balance.checked_add_signed(delta).unwrap();
+    if balance == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        set_entry(env, &key, &balance);
+    }
+}
+
+// Writes a persistent entry and keeps it live for a while, as SoroSusu does for receipts
+fn set_entry<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(key, TOKEN_TTL_THRESHOLD, TOKEN_TTL);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,225 @@
+use super::*;
+use crate::math::encode_token_id as token_id;
+use soroban_sdk::testutils::{storage::Persistent, Address as _, Ledger};
+use soroban_sdk::{token, vec};
+use sorosusu_contracts::{SoroSusu, SoroSusuClient};
+
+fn setup(env: &Env) -> (SusuNftClient<'_>, Address) {
+    // Stands in for the SoroSusu contract, which invokes the NFT as itself
+    let sorosusu = Address::generate(env);
+    let nft = SusuNftClient::new(env, &env.register_contract(None, SusuNft));
+    nft.init(&Address::generate(env), &sorosusu);
+    (nft, sorosusu)
+}
+
+fn metadata(env: &Env, circle_id: u64, slot: u32) -> NftMetadata {
+    NftMetadata {
+        circle_id,
+        slot,
+        token: Address::generate(env),
+        contribution_amount: 1000,
+        joined_at: env.ledger().timestamp(),
+    }
+}
+
+#[test]
+fn test_mint_requires_sorosusu_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (nft, sorosusu) = setup(&env);
+    let member = Address::generate(&env);
+
+    nft.mint(&member, &token_id(1, 0), &metadata(&env, 1, 0));
+
+    // Only the bound SoroSusu contract authorized the mint
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, sorosusu);
+
+    assert_eq!(nft.owner_of(&token_id(1, 0)), member);
+    assert_eq!(nft.balance(&member), 1);
+
+    // Receipts are persistent entries of their own with a long TTL
+    let ttl = env.as_contract(&nft.address, || env.storage().persistent().get_ttl(&DataKey::Owner(token_id(1, 0))));
+    assert_eq!(ttl, TOKEN_TTL);
+
+    // The same slot cannot be minted twice
+    assert!(nft.try_mint(&member, &token_id(1, 0), &metadata(&env, 1, 0)).is_err());
+}
+
+#[test]
+fn test_mint_rejected_without_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (nft, _) = setup(&env);
+    env.set_auths(&[]);
+    let member = Address::generate(&env);
+
+    assert!(nft.try_mint(&member, &token_id(1, 0), &metadata(&env, 1, 0)).is_err());
+}
+
+#[test]
+fn test_soulbound_until_unlocked() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (nft, _) = setup(&env);
+    let member = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let id = token_id(7, 2);
+
+    env.ledger().set_timestamp(1_000);
+    nft.mint(&member, &id, &metadata(&env, 7, 2));
+
+    // Active circle: the receipt cannot move
+    assert!(nft.try_transfer(&member, &buyer, &id).is_err());
+
+    // Circle completed: SoroSusu unlocks and upgrades the receipt
+    nft.unlock(&id);
+    nft.award_badge(&id);
+    nft.transfer(&member, &buyer, &id);
+
+    let info = nft.token_info(&id);
+    assert_eq!(info.owner, buyer);
+    assert!(info.transferable);
+    assert!(info.completed);
+    assert_eq!(info.metadata.circle_id, 7);
+    assert_eq!(info.metadata.slot, 2);
+    assert_eq!(info.metadata.joined_at, 1_000);
+    assert_eq!(nft.balance(&member), 0);
+    assert_eq!(nft.balance(&buyer), 1);
+}
+
+#[test]
+fn test_burn_on_ejection() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (nft, _) = setup(&env);
+    let member = Address::generate(&env);
+    let other = Address::generate(&env);
+    let id = token_id(3, 0);
+
+    nft.mint(&member, &id, &metadata(&env, 3, 0));
+
+    // Burning checks the holder SoroSusu names
+    assert!(nft.try_burn(&other, &id).is_err());
+
+    nft.burn(&member, &id);
+    assert_eq!(nft.balance(&member), 0);
+    assert!(nft.try_owner_of(&id).is_err());
+
+    // The slot can be reissued to a replacement member
+    nft.mint(&other, &id, &metadata(&env, 3, 0));
+    assert_eq!(nft.owner_of(&id), other);
+}
+
+#[test]
+fn test_recover_moves_soulbound_receipt() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (nft, sorosusu) = setup(&env);
+    let lost = Address::generate(&env);
+    let recovered = Address::generate(&env);
+    let id = token_id(4, 1);
+
+    env.ledger().set_timestamp(500);
+    nft.mint(&lost, &id, &metadata(&env, 4, 1));
+
+    // Recovery names the current holder
+    assert!(nft.try_recover(&recovered, &lost, &id).is_err());
+
+    nft.recover(&lost, &recovered, &id);
+
+    // Only SoroSusu authorized the move; the lost key never signed
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, sorosusu);
+
+    let info = nft.token_info(&id);
+    assert_eq!(info.owner, recovered);
+    assert!(!info.transferable);
+    assert_eq!(info.metadata.joined_at, 500);
+    assert_eq!(nft.balance(&lost), 0);
+    assert_eq!(nft.balance(&recovered), 1);
+}
+
+#[test]
+fn test_init_requires_admin_auth() {
+    let env = Env::default();
+    let nft = SusuNftClient::new(&env, &env.register_contract(None, SusuNft));
+    let admin = Address::generate(&env);
+    let sorosusu = Address::generate(&env);
+
+    // Without the deployer's signature no one can bind the NFT
+    assert!(nft.try_init(&admin, &sorosusu).is_err());
+
+    env.mock_all_auths();
+    nft.init(&admin, &sorosusu);
+    assert_eq!(env.auths()[0].0, admin);
+    assert!(nft.try_init(&admin, &sorosusu).is_err());
+}
+
+#[test]
+fn test_sorosusu_drives_receipt_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let new1 = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let sorosusu_id = env.register_contract(None, SoroSusu);
+    let sorosusu = SoroSusuClient::new(&env, &sorosusu_id);
+    let nft_id = env.register_contract(None, SusuNft);
+    let nft = SusuNftClient::new(&env, &nft_id);
+    nft.init(&admin, &sorosusu_id);
+
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_admin = token::StellarAssetClient::new(&env, &token);
+    for user in [&user1, &user2, &user3] {
+        token_admin.mint(user, &2000);
+    }
+
+    sorosusu.init(&admin);
+    sorosusu.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+    // Joining mints a receipt for the slot
+    let circle_id = sorosusu.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_id.clone()));
+    sorosusu.join_circle(&user1, &circle_id);
+    sorosusu.join_circle(&user2, &circle_id);
+    let id = token_id(circle_id, 0);
+    assert_eq!(nft.owner_of(&id), user1);
+    assert_eq!(nft.token_info(&id).metadata.slot, 0);
+
+    // Paying in leaves it locked while the circle runs
+    sorosusu.deposit(&user1, &circle_id, &1000);
+    sorosusu.deposit(&user2, &circle_id, &1000);
+    assert!(nft.try_transfer(&user1, &buyer, &id).is_err());
+
+    // Recovering the slot moves the receipt to the new address
+    sorosusu.set_recovery_guardians(&user1, &circle_id, &vec![&env, guardian.clone()], &1);
+    sorosusu.recover_slot(&guardian, &circle_id, &user1, &new1);
+    env.ledger().set_timestamp(sorosusu.get_pending_recovery(&circle_id, &user1).unwrap().executable_at);
+    sorosusu.execute_recovery(&circle_id, &user1);
+    assert_eq!(nft.owner_of(&id), new1);
+    assert_eq!(nft.balance(&user1), 0);
+
+    // Completing the circle unlocks the receipt
+    sorosusu.distribute_payout(&creator, &circle_id);
+    sorosusu.deposit(&new1, &circle_id, &1000);
+    sorosusu.deposit(&user2, &circle_id, &1000);
+    sorosusu.distribute_payout(&creator, &circle_id);
+    assert!(nft.token_info(&id).transferable);
+    nft.transfer(&new1, &buyer, &id);
+    assert_eq!(nft.owner_of(&id), buyer);
+
+    // Dissolving a circle burns its receipts
+    let other_id = sorosusu.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_id.clone()));
+    sorosusu.join_circle(&user3, &other_id);
+    assert_eq!(nft.owner_of(&token_id(other_id, 0)), user3);
+    sorosusu.dissolve_circle(&creator, &other_id);
+    assert!(nft.try_owner_of(&token_id(other_id, 0)).is_err());
+}
@@ -0,0 +1,196 @@
+// Off-chain projection of a SoroSusu circle. Uses the contract's own arithmetic
+// (susu_math.rs) so what the app shows before joining matches what the chain charges.
+use std::collections::BTreeMap;
+
+#[path = "../../../susu_math.rs"]
+pub mod math;
+
+// --- DATA STRUCTURES ---
+
+// Mirrors the CircleInfo fields that drive the money flows
+#[derive(Clone, Debug)]
+pub struct CircleParams {
+    pub contribution_amount: i128,
+    pub members: u32,
+    pub start_time: u64,
+    pub cycle_duration: u64,
+    pub grace_period: u64,
+    pub insurance_fee_bps: u32,
+    pub late_fee_bps: u32,
+    pub protocol_fee_bps: u32, // As set with set_protocol_fee; 0 if none
+    pub insurance_balance: i128, // Fund at the start of the simulation
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Behaviour {
+    OnTime,
+    // Pays in full this many seconds after the round's due time
+    Late(u64),
+    // Never pays; the insurance fund has to cover the round
+    Default,
+}
+
+// Members pay on time unless the scenario says otherwise
+#[derive(Clone, Debug, Default)]
+pub struct Scenario {
+    behaviour: BTreeMap<(u32, u32), Behaviour>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn late(mut self, round: u32, member: u32, delay: u64) -> Self {
+        self.behaviour.insert((round, member), Behaviour::Late(delay));
+        self
+    }
+
+    pub fn default_on(mut self, round: u32, member: u32) -> Self {
+        self.behaviour.insert((round, member), Behaviour::Default);
+        self
+    }
+
+    pub fn behaviour(&self, round: u32, member: u32) -> Behaviour {
+        self.behaviour.get(&(round, member)).copied().unwrap_or(Behaviour::OnTime)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlowKind {
+    Contribution,
+    InsuranceFee,
+    // Paid with a late contribution into the circle's reserve
+    LateFee,
+    // The fund paid the member's contribution; the member owes it back
+    InsuranceCover,
+    // Net of the protocol fee
+    Payout,
+    ProtocolFee,
+}
+
+// One line of the timeline. `amount` is from the member's point of view:
+// negative leaves their wallet, positive arrives in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CashFlow {
+    pub round: u32,
+    pub time: u64,
+    pub member: u32,
+    pub kind: FlowKind,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemberSummary {
+    pub paid: i128,
+    pub received: i128,
+    pub late_fees: i128, // Included in `paid`
+    pub insurance_debt: i128,
+    pub net: i128, // received - paid
+}
+
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    pub timeline: Vec<CashFlow>,
+    pub members: Vec<MemberSummary>,
+    pub insurance_balance: i128,
+    pub group_reserve: i128, // Late fees collected over the circle
+    pub protocol_fees: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimError {
+    InvalidParams(&'static str),
+    // A default the insurance fund could not cover; the contract would never pay out
+    Stalled { round: u32, member: u32 },
+}
+
+// --- SIMULATION ---
+
+// Runs the circle to completion: one round per member, paid out in join order.
+pub fn simulate(params: &CircleParams, scenario: &Scenario) -> Result<Simulation, SimError> {
+    if params.members == 0 || params.members > 64 {
+        return Err(SimError::InvalidParams("Members must be between 1 and 64"));
+    }
+    if params.contribution_amount < 0 {
+        return Err(SimError::InvalidParams("Amount cannot be negative"));
+    }
+    if params.insurance_fee_bps > 10000 || params.late_fee_bps > 10000 || params.protocol_fee_bps > 10000 {
+        return Err(SimError::InvalidParams("Basis points cannot exceed 10000"));
+    }
+
+    let fee = math::insurance_fee(params.contribution_amount, params.insurance_fee_bps);
+    let due = math::round_due(params.contribution_amount, params.insurance_fee_bps);
+
+    let mut timeline = Vec::new();
+    let mut members = vec![MemberSummary::default(); params.members as usize];
+    let mut insurance_balance = params.insurance_balance;
+    let mut group_reserve = 0;
+    let mut protocol_fees = 0;
+
+    for round in 0..params.members {
+        // Fixed schedule: round N is due N + 1 cycles after the start
+        let due_time = params.start_time + (round as u64 + 1) * params.cycle_duration;
+        let settle_time = due_time + params.grace_period + 1;
+        let mut insurance_used = false;
+        let mut contributors = 0;
+        let mut payout_time = due_time;
+
+        for member in 0..params.members {
+            let summary = &mut members[member as usize];
+            let time = match scenario.behaviour(round, member) {
+                Behaviour::OnTime => due_time,
+                Behaviour::Late(delay) => due_time + delay,
+                Behaviour::Default => {
+                    // Insurance covers one default per round, if the fund can afford it
+                    if insurance_used || insurance_balance < params.contribution_amount {
+                        return Err(SimError::Stalled { round, member });
+                    }
+                    insurance_used = true;
+                    insurance_balance -= params.contribution_amount;
+                    summary.insurance_debt += params.contribution_amount;
+                    timeline.push(CashFlow { round, time: settle_time, member, kind: FlowKind::InsuranceCover, amount: 0 });
+                    contributors += 1;
+                    payout_time = payout_time.max(settle_time);
+                    continue;
+                }
+            };
+
+            if time > due_time + params.grace_period {
+                let late_fee = math::late_fee(due, params.late_fee_bps);
+                summary.late_fees += late_fee;
+                summary.paid += late_fee;
+                group_reserve += late_fee;
+                timeline.push(CashFlow { round, time, member, kind: FlowKind::LateFee, amount: -late_fee });
+            }
+
+            summary.paid += due;
+            insurance_balance += fee;
+            timeline.push(CashFlow { round, time, member, kind: FlowKind::Contribution, amount: -params.contribution_amount });
+            timeline.push(CashFlow { round, time, member, kind: FlowKind::InsuranceFee, amount: -fee });
+            contributors += 1;
+            payout_time = payout_time.max(time);
+        }
+
+        // The fee comes off the gross pot before the recipient is paid
+        let pot = math::pot(params.contribution_amount, contributors);
+        let protocol_fee = math::protocol_fee(pot, params.protocol_fee_bps);
+        let payout = pot - protocol_fee;
+        protocol_fees += protocol_fee;
+
+        members[round as usize].received += payout;
+        if protocol_fee > 0 {
+            timeline.push(CashFlow { round, time: payout_time, member: round, kind: FlowKind::ProtocolFee, amount: -protocol_fee });
+        }
+        timeline.push(CashFlow { round, time: payout_time, member: round, kind: FlowKind::Payout, amount: payout });
+    }
+
+    for summary in members.iter_mut() {
+        summary.net = summary.received - summary.paid;
+    }
+
+    Ok(Simulation { timeline, members, insurance_balance, group_reserve, protocol_fees })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,97 @@
+use super::*;
+
+fn params() -> CircleParams {
+    CircleParams {
+        contribution_amount: 1000,
+        members: 3,
+        start_time: 0,
+        cycle_duration: 604800,
+        grace_period: 86400,
+        insurance_fee_bps: 1000, // 10%
+        late_fee_bps: 100,       // 1%
+        protocol_fee_bps: 50,    // 0.5%
+        insurance_balance: 0,
+    }
+}
+
+#[test]
+fn test_all_on_time() {
+    let sim = simulate(&params(), &Scenario::new()).unwrap();
+
+    // Each member pays 3 x 1100 and receives one 3000 pot less 15 protocol fee
+    for summary in sim.members.iter() {
+        assert_eq!(summary.paid, 3300);
+        assert_eq!(summary.received, 2985);
+        assert_eq!(summary.net, -315);
+        assert_eq!(summary.late_fees, 0);
+    }
+    assert_eq!(sim.insurance_balance, 900);
+    assert_eq!(sim.group_reserve, 0);
+    assert_eq!(sim.protocol_fees, 45);
+
+    // Payouts follow join order on the fixed schedule
+    let payouts: Vec<&CashFlow> = sim.timeline.iter().filter(|flow| flow.kind == FlowKind::Payout).collect();
+    assert_eq!(payouts.len(), 3);
+    assert_eq!(payouts[2].member, 2);
+    assert_eq!(payouts[2].time, 3 * 604800);
+}
+
+#[test]
+fn test_late_fee_only_after_grace() {
+    let scenario = Scenario::new().late(0, 1, 3600).late(1, 2, 2 * 86400);
+    let sim = simulate(&params(), &scenario).unwrap();
+
+    assert_eq!(sim.members[1].late_fees, 0);
+    // 1% of the 1100 due for the round
+    assert_eq!(sim.members[2].late_fees, 11);
+    assert_eq!(sim.group_reserve, 11);
+
+    // The fee is paid with the late contribution
+    let fee = sim.timeline.iter().find(|flow| flow.kind == FlowKind::LateFee).unwrap();
+    assert_eq!(fee.amount, -11);
+    assert_eq!(sim.members[2].paid, 3311);
+
+    // The round pays out once the late payment lands
+    let payout = sim.timeline.iter().find(|flow| flow.kind == FlowKind::Payout && flow.round == 1).unwrap();
+    assert_eq!(payout.time, 2 * 604800 + 2 * 86400);
+}
+
+#[test]
+fn test_default_covered_by_insurance() {
+    let mut params = params();
+    params.insurance_balance = 1000;
+
+    let sim = simulate(&params, &Scenario::new().default_on(1, 0)).unwrap();
+
+    // 1000 + 300 (round 0) - 1000 cover + 200 (round 1) + 300 (round 2)
+    assert_eq!(sim.insurance_balance, 800);
+    assert_eq!(sim.members[0].insurance_debt, 1000);
+    assert_eq!(sim.members[0].paid, 2200);
+
+    // The covered round still pays a full pot
+    assert_eq!(sim.members[1].received, 2985);
+}
+
+#[test]
+fn test_uncovered_default_stalls() {
+    let mut params = params();
+    params.insurance_balance = 0;
+    params.insurance_fee_bps = 0;
+
+    let scenario = Scenario::new().default_on(0, 2);
+    assert_eq!(sim_error(&params, &scenario), Some(SimError::Stalled { round: 0, member: 2 }));
+}
+
+#[test]
+fn test_shares_contract_arithmetic() {
+    assert_eq!(math::insurance_fee(1000, 250), 25);
+    assert_eq!(math::late_fee(500, 100), 5);
+    assert_eq!(math::keeper_bounty(20_000, 500, 1_000), 100);
+    assert_eq!(math::pro_rata(600, 500, 1000), 300);
+    assert_eq!(math::creator_bond(1000, 5, 1000), 500);
+    assert_eq!(math::protocol_fee(3000, 50), 15);
+}
+
+fn sim_error(params: &CircleParams, scenario: &Scenario) -> Option<SimError> {
+    simulate(params, scenario).err()
+}
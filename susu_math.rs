@@ -0,0 +1,68 @@
+// Circle arithmetic shared by the contract (temp_lib.rs), the participation NFT
+// (contracts/susu-nft) and the off-chain simulator (contracts/susu-sim). Plain integer
+// maths with no Soroban types, so every side agrees on fees, penalties, payouts and
+// token IDs.
+
+pub const BPS_DENOMINATOR: i128 = 10000;
+pub const MAX_BOUNTY_BPS: i128 = 50; // 0.5%
+
+// Share of `amount` at `bps` basis points, rounded down
+pub fn bps_of(amount: i128, bps: u32) -> i128 {
+    (amount * bps as i128) / BPS_DENOMINATOR
+}
+
+// Paid into the insurance fund on top of every contribution
+pub fn insurance_fee(contribution_amount: i128, insurance_fee_bps: u32) -> i128 {
+    bps_of(contribution_amount, insurance_fee_bps)
+}
+
+// What a member owes for one round: contribution plus insurance fee
+pub fn round_due(contribution_amount: i128, insurance_fee_bps: u32) -> i128 {
+    contribution_amount + insurance_fee(contribution_amount, insurance_fee_bps)
+}
+
+// Charged once per round on whatever was still outstanding at the deadline
+pub fn late_fee(outstanding: i128, late_fee_bps: u32) -> i128 {
+    bps_of(outstanding, late_fee_bps)
+}
+
+// Gross pot for a round: one contribution per member who paid or was covered
+pub fn pot(contribution_amount: i128, contributors: u32) -> i128 {
+    contribution_amount * contributors as i128
+}
+
+// Taken from the pot and sent to the treasury on every payout
+pub fn protocol_fee(pot: i128, fee_bps: u32) -> i128 {
+    bps_of(pot, fee_bps)
+}
+
+// Locked by the creator for the life of the circle: a share of its maximum TVL
+pub fn creator_bond(contribution_amount: i128, max_members: u32, bond_bps: u32) -> i128 {
+    bps_of(contribution_amount * max_members as i128, bond_bps)
+}
+
+// Keeper reward: the configured bounty, capped at a slice of the pot and by the pool
+pub fn keeper_bounty(pot: i128, bounty: i128, pool_balance: i128) -> i128 {
+    bounty.min(pot * MAX_BOUNTY_BPS / BPS_DENOMINATOR).min(pool_balance).max(0)
+}
+
+// Scales a positive position down when the circle holds less than it owes
+pub fn pro_rata(position: i128, held: i128, owed: i128) -> i128 {
+    if position <= 0 {
+        0
+    } else if owed > held {
+        position * held / owed
+    } else {
+        position
+    }
+}
+
+// Participation token ID: (CircleID << 64) | MemberIndex
+pub fn encode_token_id(circle_id: u64, slot: u32) -> u128 {
+    (circle_id as u128) << 64 | slot as u128
+}
+
+// Splits a participation token ID back into (CircleID, MemberIndex)
+pub fn decode_token_id(token_id: u128) -> (u64, u32) {
+    ((token_id >> 64) as u64, token_id as u32)
+}
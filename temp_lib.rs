@@ -1,5 +1,23 @@
 #![no_std]
-use soroban_sdk::{contract, contracttype, contractimpl, contractclient, Address, Env, Vec, Symbol, token, testutils::{Address as TestAddress, Arbitrary as TestArbitrary}, arbitrary::{Arbitrary, Unstructured}};
+use soroban_sdk::{contract, contracttype, contractimpl, contractclient, contractmeta, symbol_short, vec, auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation}, xdr::ToXdr, Address, BytesN, Env, IntoVal, Map, String, TryFromVal, Val, Vec, token};
+
+contractmeta!(key = "Description", val = "SoroSusu ROSCA protocol");
+
+// Emits the `binver` contract meta and the string `version()` reports from one literal
+macro_rules! build_version {
+    ($version:tt) => {
+        contractmeta!(key = "binver", val = $version);
+        const BUILD_VERSION: &str = $version;
+    };
+}
+build_version!("0.2.0");
+
+// Layout version of the data stored by this build. Bump it whenever a stored
+// struct changes shape and teach `migrate` how to convert the previous one.
+const STORAGE_VERSION: u32 = 2;
+
+#[path = "susu_math.rs"]
+pub mod math;
 
 // --- DATA STRUCTURES ---
 
@@ -8,12 +26,284 @@ use soroban_sdk::{contract, contracttype, contractimpl, contractclient, Address,
 pub enum DataKey {
     Admin,
     Circle(u64),
-    Member(Address),
+    // A member's slot in a circle (CircleID, UserAddress)
+    Member(u64, Address),
     CircleCount,
-    // New: Tracks if a user has paid for a specific circle (CircleID, UserAddress)
-    Deposit(u64, Address),
-    // New: Tracks Group Reserve balance for penalties
-    GroupReserve,
+    // Receipt for a member's fully paid round, in persistent storage (CircleID, Round, UserAddress)
+    Deposit(u64, u32, Address),
+    // Late fees a circle has collected from its members (CircleID)
+    GroupReserve(u64),
+    // Ordered list of every address that joined a circle
+    CircleMembers(u64),
+    // Admin nominated by the current admin, waiting to accept
+    PendingAdmin,
+    // Tracks whether an address holds an operational role (Role, Address)
+    Role(Role, Address),
+    // Protocol-wide circuit breaker state
+    ProtocolPause,
+    // Layout version of the stored circles and members
+    StorageVersion,
+    // Next circle `migrate` converts while a migration is under way
+    MigrationCursor,
+    // Allowlist entry and limits for a contribution token
+    Token(Address),
+    // Tracks whether a vault is approved for yield strategies
+    Vault(Address),
+    // Total vault shares held by this contract across all circles
+    VaultShares(Address),
+    // Yield strategy and vault position of a circle
+    YieldStrategy(u64),
+    // Prepaid contributions a member has escrowed (CircleID, UserAddress)
+    Credit(u64, Address),
+    // Instalments paid towards the current round (CircleID, UserAddress)
+    Partial(u64, Address),
+    // Tracks members who let keepers pull their dues (CircleID, UserAddress)
+    AutoDebit(u64, Address),
+    // Protocol-funded keeper bounties for a token
+    KeeperPool(Address),
+    // Tracks whether a keeper bounty was paid (CircleID, Round, Action)
+    BountyClaim(u64, u32, KeeperAction),
+    // Protocol fees a circle has paid that keeper bounties have not yet used up
+    BountyBudget(u64),
+    // Extra time after each round's due time before payments count as late
+    GracePeriod(u64),
+    // NFT calls that failed and wait for retry_nft (CircleID, MemberIndex)
+    PendingNft(u64, u32),
+    ProtocolFee,
+    // Late fees assessed so far in a round (CircleID, Round)
+    RoundPenalties(u64, u32),
+    // Member slots paid by a guarantee so far in a round (CircleID, Round)
+    RoundGuaranteed(u64, u32),
+    // Written once when a round pays out, in persistent storage (CircleID, Round)
+    RoundRecord(u64, u32),
+    // Creator bond rate, in basis points of a circle's maximum TVL
+    CreatorBondBps,
+    Bond(u64),
+    // Members voting to remove the creator (bitmap by member index)
+    CreatorRemovalVotes(u64),
+    Organisers(u64),
+    // Organisers who signed off a pending privileged action (CircleID, ActionHash)
+    Approvals(u64, BytesN<32>),
+    PendingCreator(u64),
+    // Members voting to swap one organiser for another (CircleID, Organiser, Replacement)
+    OrganiserVotes(u64, Address, Address),
+    // Organiser seat left by a creator removed by vote, until members fill it
+    VacantSeat(u64),
+    // Standing delegation from a member (CircleID, Member, Delegate)
+    Delegate(u64, Address, Address),
+    // Payouts and refunds waiting to be collected (CircleID, UserAddress)
+    Claimable(u64, Address),
+    // Member asked not to have payouts pushed to them (CircleID, UserAddress)
+    PullPayouts(u64, Address),
+    // Where a member's pot goes (CircleID, UserAddress)
+    PayoutSplit(u64, Address),
+    // The circle an address last joined, so it can join another once that one ends; persistent
+    MemberCircle(Address),
+    // Track record of an address in one token, in persistent storage (UserAddress, Token)
+    Reputation(Address, Address),
+    // Members below this reputation score, in the circle's token, need a guarantor to join
+    GuarantorThreshold(u64),
+    // A guarantor's backing for a member (CircleID, UserAddress)
+    Guarantee(u64, Address),
+    // Who can recover a member's slot to a new address (CircleID, UserAddress)
+    RecoveryGuardians(u64, Address),
+    // Approvals for moving a slot to a new address (CircleID, OldAddress, NewAddress)
+    RecoveryApprovals(u64, Address, Address),
+    // Approved recovery waiting out its timelock (CircleID, OldAddress)
+    PendingRecovery(u64, Address),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeeperAction {
+    Advance,
+    Settle,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ProtocolFee {
+    pub fee_bps: u32, // Share of every payout sent to the treasury
+    pub treasury: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BondStatus {
+    Locked,
+    Returned,
+    Slashed, // Paid out to the members; the creator has lost their powers
+}
+
+// Who may run a circle's privileged actions. Each action needs `threshold` of them to
+// sign off; `epoch` moves on whenever the set changes, voiding half-collected approvals.
+// Circles without an entry are run by their creator alone.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Organisers {
+    pub members: Vec<Address>,
+    pub threshold: u32,
+    pub epoch: u32,
+}
+
+// What a member lets a delegate do for them. Deposits are drawn from the member's own
+// wallet through their token allowance to the contract, up to `deposit_allowance`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegation {
+    pub deposit_allowance: i128,
+    pub can_vote: bool,
+    pub expires_at: u64, // 0 = until revoked
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MemberVote {
+    PenaltyChange,
+    Dissolution,
+    RemoveCreator,
+    ReplaceOrganiser(Address, Address), // (Organiser, Replacement)
+}
+
+// Circle funds held for a member who could not be paid directly, or chose to collect
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Claimable {
+    pub amount: i128,
+    pub credited_at: u64, // Last time anything was added; starts the recovery timeout
+}
+
+// Track record of an address in one token. It is weighted by value the address paid in
+// itself, so neither a run of tiny circles nor rounds someone else covered build it up.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reputation {
+    pub circles_completed: u32,
+    pub value_completed: i128, // Contributions the member paid themselves in circles they saw through
+    pub defaults: u32, // Rounds covered by a guarantee or the insurance fund
+    pub value_defaulted: i128,
+    pub guaranteed_defaults: u32, // Defaults by members this address vouched for
+    pub value_guaranteed_defaulted: i128,
+}
+
+// Locked by a guarantor when co-signing a join; drawn to cover the member's defaults
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Guarantee {
+    pub guarantor: Address,
+    pub amount: i128,
+    pub drawn: i128,
+}
+
+// Addresses a member trusts to move their slot if they lose their key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryGuardians {
+    pub guardians: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryApprovals {
+    pub members: u64, // Bitmap by member index
+    pub guardians: Vec<Address>,
+}
+
+// An approved recovery. It can be applied once `executable_at` has passed; until then the
+// member can cancel it with the old key if they still hold it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRecovery {
+    pub new: Address,
+    pub executable_at: u64,
+}
+
+// One destination of a split payout
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitShare {
+    pub to: Address,
+    pub bps: u32,
+}
+
+// Where a deposit's tokens come from
+enum DepositSource {
+    Wallet(Address),
+    MemberAllowance,
+}
+
+// Posted by the creator in the circle token when the circle is created
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreatorBond {
+    pub creator: Address,
+    pub amount: i128,
+    pub status: BondStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct KeeperPool {
+    pub balance: i128,
+    pub bounty: i128, // Bounty per rewarded action, before the pot cap
+}
+
+// Longest a creator may pause their own circle in one go
+const MAX_CIRCLE_PAUSE: u64 = 604800; // 1 week in seconds
+// Total a circle may spend paused over its life, so back-to-back pauses cannot freeze it
+const MAX_CIRCLE_PAUSE_TOTAL: u64 = 2419200; // 4 weeks in seconds
+const MAX_ORGANISERS: u32 = 16;
+const MAX_SPLIT_SHARES: u32 = 10;
+const MAX_GUARDIANS: u32 = 10;
+const RECOVERY_DELAY: u64 = 259200; // 3 days for the old key to cancel an approved recovery
+const CLAIM_TIMEOUT: u64 = 157680000; // 5 years before unclaimed funds can be recovered
+const ABANDONMENT_CYCLES: u64 = 2; // Cycles a funded round may sit unpaid past due + grace before it counts as abandoned
+const MAX_PAGE_SIZE: u32 = 50; // Circle IDs scanned per list_circles call
+const MIN_BOUNTY_CYCLE: u64 = 86400; // Shortest round that earns keepers a bounty
+const DAY_IN_LEDGERS: u32 = 17280;
+const HISTORY_TTL: u32 = 180 * DAY_IN_LEDGERS; // How long a history entry stays live after it is written or moved
+const HISTORY_TTL_THRESHOLD: u32 = 150 * DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PauseState {
+    pub is_paused: bool,
+    pub paused_at: u64,
+    pub total_paused_time: u64, // Sum of all finished protocol pauses, used to shift circle deadlines
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    ProtocolAdmin,
+    FeeManager,
+    Pauser,
+    Upgrader,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CircleStatus {
+    Active,
+    Completed,
+    Dissolved,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum YieldPolicy {
+    Distribute, // Accrued yield is added to the next payout
+    Reserve, // Accrued yield is added to the insurance fund
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct YieldStrategy {
+    pub vault: Address,
+    pub policy: YieldPolicy,
+    pub shares: i128, // This circle's slice of the contract's vault shares
+    pub principal: i128, // Circle funds currently in the vault, excluding yield
 }
 
 #[contracttype]
@@ -24,6 +314,9 @@ pub struct Member {
     pub contribution_count: u32,
     pub last_contribution_time: u64,
     pub is_active: bool,
+    pub total_contributed: i128, // Principal paid in, including insurance-covered rounds
+    pub total_received: i128, // Payouts received from the pot
+    pub insurance_debt: i128, // Contributions the insurance fund covered on the member's behalf
 }
 
 #[contracttype]
@@ -31,854 +324,5844 @@ pub struct Member {
 pub struct CircleInfo {
     pub id: u64,
     pub creator: Address,
-    pub contribution_amount: u64, // Optimized from i128 to u64
-    pub max_members: u16, // Optimized from u32 to u16
-    pub member_count: u16, // Track count separately from Vec
-    pub current_recipient_index: u16, // Track by index instead of Address
-    pub is_active: bool,
+    pub contribution_amount: i128, // Token amounts are i128, matching the token interface
+    pub max_members: u32,
+    pub member_count: u32, // Track count separately from Vec
+    pub current_recipient_index: u32, // Track by index instead of Address
+    pub status: CircleStatus,
     pub token: Address, // The token used (USDC, XLM)
-    pub deadline_timestamp: u64, // Deadline for on-time payments
+    pub deadline_timestamp: u64, // Due time of the current round; advances one cycle per payout
     pub cycle_duration: u64, // Duration of each payment cycle in seconds
     pub contribution_bitmap: u64,
     pub payout_bitmap: u64,
-    pub insurance_balance: u64,
+    pub insurance_balance: i128,
     pub insurance_fee_bps: u32,
     pub is_insurance_used: bool,
     pub late_fee_bps: u32,
     pub proposed_late_fee_bps: u32,
     pub proposal_votes_bitmap: u64,
-    pub nft_contract: Address,
+    pub dissolution_votes_bitmap: u64,
+    pub nft_contract: Option<Address>, // None runs the circle without participation NFTs
+    pub paused_at: u64, // Start of the current circle pause, 0 if not paused
+    pub paused_until: u64, // Automatic end of the current circle pause
+    pub pause_time_used: u64, // Circle pause time spent or booked, against MAX_CIRCLE_PAUSE_TOTAL
+    pub protocol_pause_offset: u64, // Protocol pause time already added to the deadline
 }
 
-// --- CONTRACT TRAIT ---
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenConfig {
+    pub is_listed: bool, // Delisted tokens keep their limits so existing circles still run
+    pub min_contribution: i128,
+    pub max_contribution: i128,
+    pub max_circle_tvl: i128, // Cap on contribution_amount * max_members for a single circle
+}
 
-pub trait SoroSusuTrait {
-    // Initialize the contract
-    fn init(env: Env, admin: Address);
-    
-    // Create a new savings circle
-    fn create_circle(env: Env, creator: Address, amount: u64, max_members: u16, token: Address, cycle_duration: u64, insurance_fee_bps: u32, nft_contract: Address) -> u64;
+#[contracttype]
+#[derive(Clone)]
+pub struct PartialPayment {
+    pub round: u32, // Round the instalments belong to; stale records count as nothing paid
+    pub paid: i128,
+    pub penalized: bool, // Late fee already charged on the remainder for this round
+    pub late_fee: i128, // Owed on top of contribution plus insurance fee once penalized
+}
 
-    // Join an existing circle
-    fn join_circle(env: Env, user: Address, circle_id: u64);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositReceipt {
+    pub amount: i128, // Contribution plus insurance fee
+    pub paid_at: u64, // When the round was paid in full
+    pub late: bool, // Finished after the due time plus grace
+}
 
-    // Make a deposit (Pay your weekly/monthly due)
-    fn deposit(env: Env, user: Address, circle_id: u64);
+// Permanent history of a paid-out round. Bitmaps are indexed by member slot.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundRecord {
+    pub round: u32,
+    pub due_time: u64,
+    pub paid_out_at: u64,
+    pub contributors: u64,
+    pub late_payers: u64,
+    pub insurance_covered: u64,
+    pub guarantee_covered: u64,
+    pub recipient: Address,
+    pub gross_payout: i128,
+    pub net_payout: i128, // What the recipient received, after fees and with any yield
+    pub protocol_fee: i128,
+    pub penalties: i128, // Late fees assessed during the round
+}
 
-    // Trigger insurance to cover a default
-    fn trigger_insurance_coverage(env: Env, caller: Address, circle_id: u64, member: Address);
+#[contracttype]
+#[derive(Clone)]
+pub struct CollectionReport {
+    pub collected: Vec<Address>,
+    pub skipped: Vec<Address>, // Allowance or balance too low
+}
 
-    // Propose a change to the late fee penalty
-    fn propose_penalty_change(env: Env, user: Address, circle_id: u64, new_bps: u32);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NftMetadata {
+    pub circle_id: u64,
+    pub slot: u32,
+    pub token: Address,
+    pub contribution_amount: i128,
+    pub joined_at: u64,
+}
 
-    // Vote on the current proposal
-    fn vote_penalty_change(env: Env, user: Address, circle_id: u64);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NftAction {
+    Mint(NftMetadata),
+    Burn,
+    Unlock,
+    AwardBadge,
+    Recover(Address), // Move the receipt to a member's new address
+}
 
-    // Eject a member (burns NFT)
-    fn eject_member(env: Env, caller: Address, circle_id: u64, member: Address);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingNft {
+    pub holder: Address,
+    pub action: NftAction,
 }
 
-#[contractclient(name = "SusuNftClient")]
-pub trait SusuNftTrait {
-    fn mint(env: Env, to: Address, token_id: u128);
-    fn burn(env: Env, from: Address, token_id: u128);
+#[contracttype]
+#[derive(Clone)]
+pub struct ScheduledRound {
+    pub round: u32,
+    pub due_time: u64,
+    pub recipient: Option<Address>, // None while the slot is still unfilled
 }
 
-// --- IMPLEMENTATION ---
+// --- LEGACY LAYOUTS (read only by `migrate`) ---
 
-#[contract]
-pub struct SoroSusu;
+// Storage version 1, as the original contract stored it. Its u16 counts can only reach
+// the ledger as u32 values, so that is how they are read back here.
+#[contracttype]
+#[derive(Clone)]
+pub struct MemberV1 {
+    pub address: Address,
+    pub index: u32,
+    pub contribution_count: u32,
+    pub last_contribution_time: u64,
+    pub is_active: bool,
+}
 
-#[contractimpl]
-impl SoroSusuTrait for SoroSusu {
-    fn init(env: Env, admin: Address) {
-        // Initialize the circle counter to 0 if it doesn't exist
-        if !env.storage().instance().has(&DataKey::CircleCount) {
-            env.storage().instance().set(&DataKey::CircleCount, &0u64);
-        }
-        // Set the admin
-        env.storage().instance().set(&DataKey::Admin, &admin);
-    }
+#[contracttype]
+#[derive(Clone)]
+pub struct CircleInfoV1 {
+    pub id: u64,
+    pub creator: Address,
+    pub contribution_amount: u64,
+    pub max_members: u32,
+    pub member_count: u32,
+    pub current_recipient_index: u32,
+    pub is_active: bool,
+    pub token: Address,
+    pub deadline_timestamp: u64,
+    pub cycle_duration: u64,
+    pub contribution_bitmap: u64,
+    pub payout_bitmap: u64,
+    pub insurance_balance: u64,
+    pub insurance_fee_bps: u32,
+    pub is_insurance_used: bool,
+    pub late_fee_bps: u32,
+    pub proposed_late_fee_bps: u32,
+    pub proposal_votes_bitmap: u64,
+    pub nft_contract: Address,
+}
 
-    fn create_circle(env: Env, creator: Address, amount: u64, max_members: u16, token: Address, cycle_duration: u64, insurance_fee_bps: u32, nft_contract: Address) -> u64 {
-        // 1. Get the current Circle Count
-        let mut circle_count: u64 = env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0);
-        
-        // 2. Increment the ID for the new circle
-        circle_count += 1;
+// Storage keys version 1 used that no longer match `DataKey`
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKeyV1 {
+    Member(Address),
+    GroupReserve,
+}
 
-        if max_members > 64 {
-            panic!("Max members cannot exceed 64 for optimization");
-        }
+// --- CONTRACT TRAIT ---
 
-        if insurance_fee_bps > 10000 {
-            panic!("Insurance fee cannot exceed 100%");
-        }
+pub trait SoroSusuTrait {
+    // Initialize the contract (once, signed by the admin)
+    fn init(env: Env, admin: Address);
 
-        // 3. Create the Circle Data Struct
-        let current_time = env.ledger().timestamp();
-        let new_circle = CircleInfo {
-            id: circle_count,
-            creator: creator.clone(),
-            contribution_amount: amount,
-            max_members,
-            member_count: 0,
-            current_recipient_index: 0,
-            is_active: true,
-            token,
-            deadline_timestamp: current_time + cycle_duration,
-            cycle_duration,
-            contribution_bitmap: 0,
-            payout_bitmap: 0,
-            insurance_balance: 0,
-            insurance_fee_bps,
-            is_insurance_used: false,
-            late_fee_bps: 100, // Default 1%
-            proposed_late_fee_bps: 0,
-            proposal_votes_bitmap: 0,
-            nft_contract,
-        };
+    // Nominate a new protocol admin (step 1 of 2)
+    fn propose_admin(env: Env, admin: Address, new_admin: Address);
 
-        // 4. Save the Circle and the new Count
-        env.storage().instance().set(&DataKey::Circle(circle_count), &new_circle);
-        env.storage().instance().set(&DataKey::CircleCount, &circle_count);
+    // Accept a pending admin nomination (step 2 of 2)
+    fn accept_admin(env: Env, new_admin: Address);
 
-        // 5. Initialize Group Reserve if not exists
-        if !env.storage().instance().has(&DataKey::GroupReserve) {
-            env.storage().instance().set(&DataKey::GroupReserve, &0u64);
-        }
+    // Grant an operational role (admin only)
+    fn grant_role(env: Env, admin: Address, role: Role, account: Address);
 
-        // 6. Return the new ID
-        circle_count
-    }
+    // Revoke an operational role (admin only)
+    fn revoke_role(env: Env, admin: Address, role: Role, account: Address);
 
-    fn join_circle(env: Env, user: Address, circle_id: u64) {
-        // 1. Authorization: The user MUST sign this transaction
-        user.require_auth();
+    // Check whether an address holds a role
+    fn has_role(env: Env, role: Role, account: Address) -> bool;
 
-        // 2. Retrieve the circle data
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    // Get the current protocol admin
+    fn get_admin(env: Env) -> Address;
 
-        // 3. Check if the circle is full
-        if circle.member_count >= circle.max_members {
-            panic!("Circle is full");
-        }
+    // Get the admin nomination awaiting acceptance, if any
+    fn get_pending_admin(env: Env) -> Option<Address>;
 
-        // 4. Check if user is already a member to prevent duplicates
-        let member_key = DataKey::Member(user.clone());
-        if env.storage().instance().has(&member_key) {
-            panic!("User is already a member");
-        }
+    // Halt value-moving operations on every circle (pauser only)
+    fn pause_protocol(env: Env, caller: Address);
 
-        // 5. Create and store the new member
-        let new_member = Member {
-            address: user.clone(),
-            index: circle.member_count as u32,
-            contribution_count: 0,
-            last_contribution_time: 0,
-            is_active: true,
-        };
-        
-        // 6. Store the member and update circle count
-        env.storage().instance().set(&member_key, &new_member);
-        circle.member_count += 1;
-        
-        // 7. Save the updated circle back to storage
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+    // Resume the protocol (pauser only)
+    fn unpause_protocol(env: Env, caller: Address);
 
-        // 8. Mint Participation NFT
-        // Token ID = (CircleID << 64) | MemberIndex
-        let token_id = (circle_id as u128) << 64 | (new_member.index as u128);
-        let client = SusuNftClient::new(&env, &circle.nft_contract);
-        client.mint(&user, &token_id);
-    }
+    // Halt value-moving operations on one circle for up to a week (organisers only)
+    fn pause_circle(env: Env, caller: Address, circle_id: u64, duration: u64);
 
-    fn deposit(env: Env, user: Address, circle_id: u64) {
-        // 1. Authorization: The user must sign this!
-        user.require_auth();
+    // Lift a circle pause early (organisers only)
+    fn unpause_circle(env: Env, caller: Address, circle_id: u64);
 
-        // 2. Load the Circle Data
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    // Check whether the protocol or the given circle is paused
+    fn is_paused(env: Env, circle_id: u64) -> bool;
 
-        // 3. Check if user is actually a member
-        let member_key = DataKey::Member(user.clone());
-        let mut member: Member = env.storage().instance().get(&member_key)
-            .unwrap_or_else(|| panic!("User is not a member of this circle"));
+    // Replace the contract code, keeping storage (upgrader only)
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>);
 
-        if !member.is_active {
-            panic!("Member is ejected");
-        }
+    // Convert the next stored circle and its members to the current layout; true once all are done (upgrader only)
+    fn migrate(env: Env, caller: Address, members: Vec<Address>) -> bool;
 
-        // 4. Create the Token Client
-        let client = token::Client::new(&env, &circle.token);
+    // Get the build version of the running contract
+    fn version(env: Env) -> String;
 
-        // 5. Check if payment is late and apply penalty if needed
-        let current_time = env.ledger().timestamp();
-        let mut penalty_amount = 0u64;
+    // Get the layout version of the stored data
+    fn storage_version(env: Env) -> u32;
 
-        if current_time > circle.deadline_timestamp {
-            // Calculate penalty based on dynamic rate
-            penalty_amount = (circle.contribution_amount * circle.late_fee_bps as u64) / 10000;
-            
-            // Update Group Reserve balance
-            let mut reserve_balance: u64 = env.storage().instance().get(&DataKey::GroupReserve).unwrap_or(0);
-            reserve_balance += penalty_amount;
-            env.storage().instance().set(&DataKey::GroupReserve, &reserve_balance);
-        }
+    // Approve a token for new circles, or update its limits (admin only)
+    fn list_token(env: Env, admin: Address, token: Address, min_contribution: i128, max_contribution: i128, max_circle_tvl: i128);
 
-        // 6. Calculate Insurance Fee and Transfer the full amount from user
-        let insurance_fee = ((circle.contribution_amount as u128 * circle.insurance_fee_bps as u128) / 10000) as u64;
-        let total_amount = circle.contribution_amount + insurance_fee;
+    // Stop new circles from using a token; existing circles are unaffected (admin only)
+    fn delist_token(env: Env, admin: Address, token: Address);
 
-        client.transfer(
-            &user, 
-            &env.current_contract_address(), 
-            &total_amount
-        );
+    // Get the allowlist entry for a token, if any
+    fn get_token_config(env: Env, token: Address) -> Option<TokenConfig>;
 
-        if insurance_fee > 0 {
-            circle.insurance_balance += insurance_fee;
-        }
+    // Approve or revoke a vault for yield strategies (admin only)
+    fn set_vault_approval(env: Env, admin: Address, vault: Address, approved: bool);
 
-        // 7. Update member contribution info
-        member.contribution_count += 1;
-        member.last_contribution_time = current_time;
-        
-        // 8. Save updated member info
-        env.storage().instance().set(&member_key, &member);
+    // Choose the vault and yield policy for a circle (organisers only)
+    fn set_yield_strategy(env: Env, caller: Address, circle_id: u64, vault: Address, policy: YieldPolicy);
 
-        // 9. Update circle deadline for next cycle
-        circle.deadline_timestamp = current_time + circle.cycle_duration;
-        circle.contribution_bitmap |= 1 << member.index;
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
-    }
+    // Move idle circle funds into its vault, keeping the next payout liquid
+    fn sweep_to_vault(env: Env, circle_id: u64) -> i128;
 
-    fn trigger_insurance_coverage(env: Env, caller: Address, circle_id: u64, member: Address) {
-        caller.require_auth();
+    // Get the yield strategy of a circle, if any
+    fn get_yield_strategy(env: Env, circle_id: u64) -> Option<YieldStrategy>;
 
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    // Get the yield a circle has earned but not yet harvested
+    fn get_accrued_yield(env: Env, circle_id: u64) -> i128;
 
-        // Only creator can trigger insurance
-        if caller != circle.creator {
-            panic!("Unauthorized: Only creator can trigger insurance");
-        }
+    // Pay the pot to the current recipient once everyone has contributed (organisers only)
+    fn distribute_payout(env: Env, caller: Address, circle_id: u64);
 
-        // Check if insurance was already used this cycle
-        if circle.is_insurance_used {
-            panic!("Insurance already used this cycle");
-        }
+    // Fund the keeper bounty pool for a token (anyone can top up)
+    fn fund_keeper_pool(env: Env, funder: Address, token: Address, amount: i128);
 
-        // Check if there is enough balance
-        if circle.insurance_balance < circle.contribution_amount {
-            panic!("Insufficient insurance balance");
-        }
+    // Set the bounty paid per keeper action for a token (fee manager only)
+    fn set_keeper_bounty(env: Env, caller: Address, token: Address, bounty: i128);
 
-        let member_key = DataKey::Member(member.clone());
-        let member_info: Member = env.storage().instance().get(&member_key).unwrap();
+    // Get the keeper pool for a token, if any
+    fn get_keeper_pool(env: Env, token: Address) -> Option<KeeperPool>;
 
-        if !member_info.is_active {
-            panic!("Member is ejected");
-        }
+    // Set the fee taken from every payout and where it goes (fee manager only)
+    fn set_protocol_fee(env: Env, caller: Address, fee_bps: u32, treasury: Address);
 
-        // Mark member as contributed in the bitmap
-        if (circle.contribution_bitmap & (1 << member_info.index)) != 0 {
-            panic!("Member already contributed");
-        }
+    // Get the protocol fee, if one is set
+    fn get_protocol_fee(env: Env) -> Option<ProtocolFee>;
 
-        circle.contribution_bitmap |= 1 << member_info.index;
-        circle.insurance_balance -= circle.contribution_amount;
-        circle.is_insurance_used = true;
+    // Set the bond creators lock for new circles, as a share of max TVL (fee manager only)
+    fn set_creator_bond(env: Env, caller: Address, bond_bps: u32);
 
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
-    }
+    // Get the creator bond rate for new circles
+    fn get_creator_bond_bps(env: Env) -> u32;
 
-    fn propose_penalty_change(env: Env, user: Address, circle_id: u64, new_bps: u32) {
-        user.require_auth();
-        
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        
-        // Check if user is a member
-        let member_key = DataKey::Member(user.clone());
-        let member: Member = env.storage().instance().get(&member_key).expect("User is not a member");
+    // Pay out a fully funded round and start the next one (anyone can call, earns a bounty)
+    fn advance_round(env: Env, keeper: Address, circle_id: u64) -> i128;
 
-        if !member.is_active {
-            panic!("Member is ejected");
-        }
+    // Cover defaults from insurance once the deadline has passed (anyone can call, earns a bounty)
+    fn settle_round(env: Env, keeper: Address, circle_id: u64) -> i128;
 
-        if new_bps > 10000 {
-            panic!("Penalty cannot exceed 100%");
-        }
+    // Pay several rounds ahead; credit is applied automatically as each round starts
+    fn prepay(env: Env, user: Address, circle_id: u64, rounds: u32);
 
-        // Set proposal
+    // Get a member's unused prepaid credit
+    fn get_credit(env: Env, circle_id: u64, user: Address) -> i128;
+    
+    // Create a new savings circle
+    #[allow(clippy::too_many_arguments)]
+    fn create_circle(env: Env, creator: Address, amount: i128, max_members: u32, token: Address, cycle_duration: u64, insurance_fee_bps: u32, nft_contract: Option<Address>) -> u64;
+
+    // Join an existing circle
+    fn join_circle(env: Env, user: Address, circle_id: u64);
+
+    // Join with a guarantor who co-signs and locks a guarantee against defaults
+    fn join_with_guarantor(env: Env, user: Address, circle_id: u64, guarantor: Address, guarantee: i128);
+
+    // Require a guarantor for members below a reputation score in the circle's token; 0 to turn off (organisers only)
+    fn set_guarantor_threshold(env: Env, caller: Address, circle_id: u64, min_score: i128);
+
+    // Get the guarantee backing a member, if any
+    fn get_guarantee(env: Env, circle_id: u64, user: Address) -> Option<Guarantee>;
+
+    // Get an address's track record across circles in a token
+    fn get_reputation(env: Env, user: Address, token: Address) -> Reputation;
+
+    // Make a deposit (Pay your weekly/monthly due, in full or in instalments)
+    fn deposit(env: Env, user: Address, circle_id: u64, amount: i128);
+
+    // Pay whatever a member still owes this round from the payer's wallet (anyone can pay)
+    fn deposit_for(env: Env, payer: Address, member: Address, circle_id: u64);
+
+    // Let a delegate deposit from the member's wallet up to a limit, and optionally vote
+    fn set_delegate(env: Env, user: Address, circle_id: u64, delegate: Address, deposit_allowance: i128, can_vote: bool, expires_at: u64);
+
+    // Withdraw a delegation
+    fn revoke_delegate(env: Env, user: Address, circle_id: u64, delegate: Address);
+
+    // Get a member's delegation to an address, if any
+    fn get_delegate(env: Env, circle_id: u64, user: Address, delegate: Address) -> Option<Delegation>;
+
+    // Pay a member's outstanding round from their allowance (delegates only)
+    fn delegate_deposit(env: Env, delegate: Address, member: Address, circle_id: u64);
+
+    // Cast a member's vote (delegates allowed to vote only)
+    fn delegate_vote(env: Env, delegate: Address, member: Address, circle_id: u64, vote: MemberVote);
+
+    // Collect payouts and refunds held for the caller
+    fn claim(env: Env, user: Address, circle_id: u64) -> i128;
+
+    // Choose to collect payouts with claim instead of having them pushed
+    fn set_pull_payouts(env: Env, user: Address, circle_id: u64, enabled: bool);
+
+    // Get the funds held for a member, if any
+    fn get_claimable(env: Env, circle_id: u64, user: Address) -> Option<Claimable>;
+
+    // Send funds left unclaimed for five years to the protocol treasury (admin only)
+    fn recover_unclaimed(env: Env, caller: Address, circle_id: u64, user: Address) -> i128;
+
+    // Send your pot to several addresses by basis points; empty to clear (locked once your round starts)
+    fn set_payout_split(env: Env, user: Address, circle_id: u64, shares: Vec<SplitShare>);
+
+    // Get where a member's pot will be sent; empty if it all goes to them
+    fn get_payout_split(env: Env, circle_id: u64, user: Address) -> Vec<SplitShare>;
+
+    // Get what a member still owes for the current round, late fee included
+    fn get_outstanding(env: Env, circle_id: u64, user: Address) -> i128;
+
+    // Set how long after each due time payments still count as on time (organisers only)
+    fn set_grace_period(env: Env, caller: Address, circle_id: u64, grace_period: u64);
+
+    // Get every round's due time and recipient
+    fn get_schedule(env: Env, circle_id: u64) -> Vec<ScheduledRound>;
+
+    // Check whether a member paid a round in full by its due time plus grace
+    fn was_paid_on_time(env: Env, circle_id: u64, round: u32, user: Address) -> bool;
+
+    // Get the receipt for a round the member paid in full, if any
+    fn get_deposit_receipt(env: Env, circle_id: u64, round: u32, user: Address) -> Option<DepositReceipt>;
+
+    // Opt in or out of keeper-driven collection against the member's token allowance
+    fn set_auto_debit(env: Env, user: Address, circle_id: u64, enabled: bool);
+
+    // Pull dues from every opted-in member who has approved enough (anyone can call)
+    fn collect_due(env: Env, circle_id: u64) -> CollectionReport;
+
+    // Trigger insurance to cover a default (organisers only)
+    fn trigger_insurance_coverage(env: Env, caller: Address, circle_id: u64, member: Address);
+
+    // Propose a change to the late fee penalty
+    fn propose_penalty_change(env: Env, user: Address, circle_id: u64, new_bps: u32);
+
+    // Vote on the current proposal
+    fn vote_penalty_change(env: Env, user: Address, circle_id: u64);
+
+    // Eject a member (burns NFT, organisers only)
+    fn eject_member(env: Env, caller: Address, circle_id: u64, member: Address);
+
+    // Dissolve the circle before the first payout and refund everyone (organisers only)
+    fn dissolve_circle(env: Env, caller: Address, circle_id: u64);
+
+    // Vote to dissolve the circle; dissolves once a majority is reached
+    fn vote_dissolution(env: Env, user: Address, circle_id: u64);
+
+    // Vote to remove the creator; slashes their bond to the members once a majority is reached
+    fn vote_remove_creator(env: Env, user: Address, circle_id: u64);
+
+    // Slash the bond of a creator who left a fully funded round unpaid long past its deadline (anyone can call)
+    fn report_abandonment(env: Env, circle_id: u64);
+
+    // Get the creator bond of a circle, if any
+    fn get_creator_bond(env: Env, circle_id: u64) -> Option<CreatorBond>;
+
+    // Replace the organiser set and its approval threshold (organisers only)
+    fn set_organisers(env: Env, caller: Address, circle_id: u64, organisers: Vec<Address>, threshold: u32);
+
+    // Get who runs a circle and how many of them must approve
+    fn get_organisers(env: Env, circle_id: u64) -> Organisers;
+
+    // Nominate a new creator, who takes over the bond (step 1 of 2, creator only)
+    fn propose_creator(env: Env, caller: Address, circle_id: u64, new_creator: Address);
+
+    // Accept a creator nomination and lock the bond (step 2 of 2)
+    fn accept_creator(env: Env, new_creator: Address, circle_id: u64);
+
+    // Get the creator nomination awaiting acceptance, if any
+    fn get_pending_creator(env: Env, circle_id: u64) -> Option<Address>;
+
+    // Vote to swap an organiser for a replacement; applied once a majority is reached
+    fn vote_replace_organiser(env: Env, user: Address, circle_id: u64, organiser: Address, replacement: Address);
+
+    // Register who may recover your slot to a new address; empty to clear
+    fn set_recovery_guardians(env: Env, user: Address, circle_id: u64, guardians: Vec<Address>, threshold: u32);
+
+    // Get a member's recovery guardians, if any
+    fn get_recovery_guardians(env: Env, circle_id: u64, user: Address) -> Option<RecoveryGuardians>;
+
+    // Approve moving a member's slot to a new address; once a majority of the other
+    // members, or enough of the member's guardians, agree, the move waits out a timelock
+    fn recover_slot(env: Env, caller: Address, circle_id: u64, old: Address, new: Address);
+
+    // Apply an approved recovery once its timelock has passed (anyone can call)
+    fn execute_recovery(env: Env, circle_id: u64, old: Address);
+
+    // Cancel an approved recovery of your slot before it is applied
+    fn cancel_recovery(env: Env, user: Address, circle_id: u64);
+
+    // Get the approved recovery waiting on a member's slot, if any
+    fn get_pending_recovery(env: Env, circle_id: u64, user: Address) -> Option<PendingRecovery>;
+
+    // Replay NFT calls that failed for a member slot (anyone can call); true once none are left
+    fn retry_nft(env: Env, circle_id: u64, slot: u32) -> bool;
+
+    // Get NFT calls still waiting for a member slot
+    fn get_pending_nft(env: Env, circle_id: u64, slot: u32) -> Vec<PendingNft>;
+
+    // Get the history record of a paid-out round
+    fn get_round_record(env: Env, circle_id: u64, round: u32) -> Option<RoundRecord>;
+
+    // List paid-out rounds from a round number onwards
+    fn list_round_records(env: Env, circle_id: u64, start: u32, limit: u32) -> Vec<RoundRecord>;
+
+    // Get the full state of a circle
+    fn get_circle(env: Env, circle_id: u64) -> CircleInfo;
+
+    // Get a member's record in a circle
+    fn get_member(env: Env, circle_id: u64, user: Address) -> Member;
+
+    // Get member addresses in join order
+    fn get_members(env: Env, circle_id: u64) -> Vec<Address>;
+
+    // Get whether each active member has paid the current round
+    fn get_contribution_status(env: Env, circle_id: u64) -> Map<Address, bool>;
+
+    // Scan up to `limit` circle IDs from `start`, optionally filtered by status or token;
+    // also returns the ID to resume from, 0 once every circle has been scanned
+    fn list_circles(env: Env, start: u64, limit: u32, status: Option<CircleStatus>, token: Option<Address>) -> (Vec<CircleInfo>, u64);
+}
+
+// Participation receipt minted on join. Receipts must stay non-transferable while the
+// circle is active; `unlock` lifts that once it completes, and `award_badge` upgrades the
+// receipt of a member who finished every round without a default. `recover` follows a
+// slot to its new address after social recovery.
+#[contractclient(name = "SusuNftClient")]
+pub trait SusuNftTrait {
+    fn mint(env: Env, to: Address, token_id: u128, metadata: NftMetadata);
+    fn burn(env: Env, from: Address, token_id: u128);
+    fn unlock(env: Env, token_id: u128);
+    fn award_badge(env: Env, token_id: u128);
+    fn recover(env: Env, from: Address, to: Address, token_id: u128);
+}
+
+// Participation token IDs are encoded in susu_math so the NFT contract shares the layout
+pub use math::{decode_token_id, encode_token_id};
+
+// Yield source for idle circle funds. `deposit` pulls `amount` of the circle token
+// from `from`; `withdraw` sends it back to `to`; `balance` includes accrued yield.
+#[contractclient(name = "VaultClient")]
+pub trait VaultTrait {
+    fn deposit(env: Env, from: Address, amount: i128);
+    fn withdraw(env: Env, to: Address, amount: i128);
+    fn balance(env: Env, owner: Address) -> i128;
+}
+
+// --- IMPLEMENTATION ---
+
+#[contract]
+pub struct SoroSusu;
+
+#[contractimpl]
+impl SoroSusuTrait for SoroSusu {
+    fn init(env: Env, admin: Address) {
+        // Can only be called once, and the admin must sign
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+        admin.require_auth();
+
+        // Initialize the circle counter to 0 if it doesn't exist
+        if !env.storage().instance().has(&DataKey::CircleCount) {
+            env.storage().instance().set(&DataKey::CircleCount, &0u64);
+        }
+        // Set the admin
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::StorageVersion, &STORAGE_VERSION);
+
+        // The admin starts out holding every operational role
+        for role in [Role::FeeManager, Role::Pauser, Role::Upgrader] {
+            env.storage().instance().set(&DataKey::Role(role, admin.clone()), &true);
+        }
+
+        env.events().publish((symbol_short!("init"),), admin);
+    }
+
+    fn propose_admin(env: Env, admin: Address, new_admin: Address) {
+        require_admin(&env, &admin);
+
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+
+        env.events().publish((symbol_short!("adm_prop"), admin), new_admin);
+    }
+
+    fn accept_admin(env: Env, new_admin: Address) {
+        new_admin.require_auth();
+
+        let pending: Address = env.storage().instance().get(&DataKey::PendingAdmin)
+            .unwrap_or_else(|| panic!("No pending admin"));
+        if pending != new_admin {
+            panic!("Unauthorized: Not the pending admin");
+        }
+
+        let old_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        // Operational roles the old admin still holds move with the admin role, so the
+        // outgoing key keeps no power; roles granted to other accounts are untouched
+        for role in [Role::FeeManager, Role::Pauser, Role::Upgrader] {
+            let old_key = DataKey::Role(role, old_admin.clone());
+            if env.storage().instance().get(&old_key).unwrap_or(false) {
+                env.storage().instance().remove(&old_key);
+                env.storage().instance().set(&DataKey::Role(role, new_admin.clone()), &true);
+            }
+        }
+
+        env.events().publish((symbol_short!("adm_acpt"), old_admin), new_admin);
+    }
+
+    fn grant_role(env: Env, admin: Address, role: Role, account: Address) {
+        require_admin(&env, &admin);
+
+        // The protocol admin is moved with propose_admin / accept_admin
+        if role == Role::ProtocolAdmin {
+            panic!("Use propose_admin to transfer the admin role");
+        }
+
+        env.storage().instance().set(&DataKey::Role(role, account.clone()), &true);
+
+        env.events().publish((symbol_short!("role_grnt"), role), account);
+    }
+
+    fn revoke_role(env: Env, admin: Address, role: Role, account: Address) {
+        require_admin(&env, &admin);
+
+        if role == Role::ProtocolAdmin {
+            panic!("Use propose_admin to transfer the admin role");
+        }
+
+        env.storage().instance().remove(&DataKey::Role(role, account.clone()));
+
+        env.events().publish((symbol_short!("role_rvk"), role), account);
+    }
+
+    fn has_role(env: Env, role: Role, account: Address) -> bool {
+        has_role(&env, role, &account)
+    }
+
+    fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap_or_else(|| panic!("Contract not initialized"))
+    }
+
+    fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    fn pause_protocol(env: Env, caller: Address) {
+        require_role(&env, Role::Pauser, &caller);
+
+        let mut state = get_pause_state(&env);
+        if state.is_paused {
+            panic!("Protocol already paused");
+        }
+
+        state.is_paused = true;
+        state.paused_at = env.ledger().timestamp();
+        env.storage().instance().set(&DataKey::ProtocolPause, &state);
+
+        env.events().publish((symbol_short!("paused"),), caller);
+    }
+
+    fn unpause_protocol(env: Env, caller: Address) {
+        require_role(&env, Role::Pauser, &caller);
+
+        let mut state = get_pause_state(&env);
+        if !state.is_paused {
+            panic!("Protocol is not paused");
+        }
+
+        // Circles pick up the paused time lazily the next time they are touched
+        state.total_paused_time += env.ledger().timestamp() - state.paused_at;
+        state.is_paused = false;
+        state.paused_at = 0;
+        env.storage().instance().set(&DataKey::ProtocolPause, &state);
+
+        env.events().publish((symbol_short!("unpaused"),), caller);
+    }
+
+    fn pause_circle(env: Env, caller: Address, circle_id: u64, duration: u64) {
+        caller.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can pause the circle
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can pause the circle");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("pause"), duration)) {
+            return;
+        }
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        if duration == 0 || duration > MAX_CIRCLE_PAUSE {
+            panic!("Pause duration must be between 1 second and 1 week");
+        }
+
+        sync_pause(&env, &mut circle);
+        if circle.paused_until != 0 {
+            panic!("Circle already paused");
+        }
+        if circle.pause_time_used + duration > MAX_CIRCLE_PAUSE_TOTAL {
+            panic!("Circle pause budget exhausted");
+        }
+
+        let current_time = env.ledger().timestamp();
+        circle.paused_at = current_time;
+        circle.paused_until = current_time + duration;
+        circle.pause_time_used += duration;
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+        env.events().publish((symbol_short!("c_paused"), circle_id), circle.paused_until);
+    }
+
+    fn unpause_circle(env: Env, caller: Address, circle_id: u64) {
+        caller.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can unpause the circle
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can unpause the circle");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("unpause"),)) {
+            return;
+        }
+
+        sync_pause(&env, &mut circle);
+        if circle.paused_until == 0 {
+            panic!("Circle is not paused");
+        }
+
+        // Credit the time actually spent paused and return the rest to the budget
+        let current_time = env.ledger().timestamp();
+        circle.deadline_timestamp += current_time - circle.paused_at;
+        circle.pause_time_used -= circle.paused_until - current_time;
+        circle.paused_at = 0;
+        circle.paused_until = 0;
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+        env.events().publish((symbol_short!("c_unpause"), circle_id), caller);
+    }
+
+    fn is_paused(env: Env, circle_id: u64) -> bool {
+        if get_pause_state(&env).is_paused {
+            return true;
+        }
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+        circle.paused_until > env.ledger().timestamp()
+    }
+
+    fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_role(&env, Role::Upgrader, &caller);
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish((symbol_short!("upgraded"), caller), new_wasm_hash);
+    }
+
+    fn migrate(env: Env, caller: Address, members: Vec<Address>) -> bool {
+        require_role(&env, Role::Upgrader, &caller);
+
+        // Deployments that predate versioning hold version 1 data
+        let from_version: u32 = env.storage().instance().get(&DataKey::StorageVersion).unwrap_or(1);
+        if from_version >= STORAGE_VERSION {
+            panic!("Storage already up to date");
+        }
+
+        // One circle per call keeps each transaction within budget; the cursor lets the
+        // upgrader resume where the last call stopped
+        let circle_count: u64 = env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0);
+        let mut cursor: u64 = env.storage().instance().get(&DataKey::MigrationCursor).unwrap_or(1);
+        if cursor <= circle_count {
+            if from_version < 2 {
+                migrate_circle_v1(&env, cursor, &members);
+            }
+            env.events().publish((symbol_short!("mig_circ"), cursor), from_version);
+            cursor += 1;
+            env.storage().instance().set(&DataKey::MigrationCursor, &cursor);
+        }
+
+        if cursor <= circle_count {
+            return false;
+        }
+
+        if from_version < 2 {
+            migrate_reserve_v1(&env);
+        }
+
+        env.storage().instance().remove(&DataKey::MigrationCursor);
+        env.storage().instance().set(&DataKey::StorageVersion, &STORAGE_VERSION);
+
+        env.events().publish((symbol_short!("migrated"), from_version), STORAGE_VERSION);
+        true
+    }
+
+    fn version(env: Env) -> String {
+        String::from_str(&env, BUILD_VERSION)
+    }
+
+    fn storage_version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::StorageVersion).unwrap_or(1)
+    }
+
+    fn list_token(env: Env, admin: Address, token: Address, min_contribution: i128, max_contribution: i128, max_circle_tvl: i128) {
+        require_admin(&env, &admin);
+
+        if min_contribution < 0 || min_contribution > max_contribution {
+            panic!("Invalid contribution limits");
+        }
+
+        if max_circle_tvl < max_contribution {
+            panic!("TVL cap must cover at least one contribution");
+        }
+
+        let config = TokenConfig {
+            is_listed: true,
+            min_contribution,
+            max_contribution,
+            max_circle_tvl,
+        };
+        env.storage().instance().set(&DataKey::Token(token.clone()), &config);
+
+        env.events().publish((symbol_short!("tok_list"), token), (min_contribution, max_contribution, max_circle_tvl));
+    }
+
+    fn delist_token(env: Env, admin: Address, token: Address) {
+        require_admin(&env, &admin);
+
+        let key = DataKey::Token(token.clone());
+        let mut config: TokenConfig = env.storage().instance().get(&key).unwrap_or_else(|| panic!("Token not listed"));
+        config.is_listed = false;
+        env.storage().instance().set(&key, &config);
+
+        env.events().publish((symbol_short!("tok_dlist"),), token);
+    }
+
+    fn get_token_config(env: Env, token: Address) -> Option<TokenConfig> {
+        env.storage().instance().get(&DataKey::Token(token))
+    }
+
+    fn set_vault_approval(env: Env, admin: Address, vault: Address, approved: bool) {
+        require_admin(&env, &admin);
+
+        if approved {
+            env.storage().instance().set(&DataKey::Vault(vault.clone()), &true);
+        } else {
+            // Circles already using the vault can still withdraw from it
+            env.storage().instance().remove(&DataKey::Vault(vault.clone()));
+        }
+
+        env.events().publish((symbol_short!("vault"), vault), approved);
+    }
+
+    fn set_yield_strategy(env: Env, caller: Address, circle_id: u64, vault: Address, policy: YieldPolicy) {
+        caller.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can choose where the pot is invested
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can set the yield strategy");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("yield"), vault.clone(), policy)) {
+            return;
+        }
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        if !env.storage().instance().get(&DataKey::Vault(vault.clone())).unwrap_or(false) {
+            panic!("Vault is not approved");
+        }
+
+        // Staying in the same vault keeps the position; switching vaults is only
+        // allowed once the old position is fully withdrawn
+        let key = DataKey::YieldStrategy(circle_id);
+        let existing: Option<YieldStrategy> = env.storage().instance().get(&key);
+        let (shares, principal) = match existing {
+            Some(existing) if existing.vault == vault => (existing.shares, existing.principal),
+            Some(existing) if existing.shares > 0 => panic!("Circle still has funds in its current vault"),
+            _ => (0, 0),
+        };
+
+        let strategy = YieldStrategy {
+            vault: vault.clone(),
+            policy,
+            shares,
+            principal,
+        };
+        env.storage().instance().set(&key, &strategy);
+
+        env.events().publish((symbol_short!("yield_set"), circle_id), vault);
+    }
+
+    fn sweep_to_vault(env: Env, circle_id: u64) -> i128 {
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        require_not_paused(&env, &mut circle);
+
+        let mut strategy: YieldStrategy = env.storage().instance().get(&DataKey::YieldStrategy(circle_id))
+            .unwrap_or_else(|| panic!("Circle has no yield strategy"));
+
+        // Keep one full pot liquid for the next payout
+        let liquid = circle_held(&env, circle_id, &circle) - strategy.principal;
+        let buffer = circle.contribution_amount * circle.member_count as i128;
+        let amount = liquid - buffer;
+        if amount <= 0 {
+            return 0;
+        }
+
+        vault_deposit(&env, &circle, &mut strategy, amount);
+        env.storage().instance().set(&DataKey::YieldStrategy(circle_id), &strategy);
+
+        amount
+    }
+
+    fn get_yield_strategy(env: Env, circle_id: u64) -> Option<YieldStrategy> {
+        env.storage().instance().get(&DataKey::YieldStrategy(circle_id))
+    }
+
+    fn get_accrued_yield(env: Env, circle_id: u64) -> i128 {
+        let strategy: Option<YieldStrategy> = env.storage().instance().get(&DataKey::YieldStrategy(circle_id));
+        match strategy {
+            Some(strategy) => (vault_value(&env, &strategy) - strategy.principal).max(0),
+            None => 0,
+        }
+    }
+
+    fn distribute_payout(env: Env, caller: Address, circle_id: u64) {
+        caller.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can trigger the payout
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can distribute the payout");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("payout"), current_round(&circle))) {
+            return;
+        }
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        require_not_paused(&env, &mut circle);
+
+        pay_out_round(&env, circle_id, &mut circle);
+    }
+
+    fn fund_keeper_pool(env: Env, funder: Address, token: Address, amount: i128) {
+        funder.require_auth();
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let client = token::Client::new(&env, &token);
+        client.transfer(&funder, &env.current_contract_address(), &amount);
+
+        let key = DataKey::KeeperPool(token.clone());
+        let mut pool: KeeperPool = env.storage().instance().get(&key).unwrap_or(KeeperPool { balance: 0, bounty: 0 });
+        pool.balance += amount;
+        env.storage().instance().set(&key, &pool);
+
+        env.events().publish((symbol_short!("kp_fund"), token, funder), amount);
+    }
+
+    fn set_keeper_bounty(env: Env, caller: Address, token: Address, bounty: i128) {
+        require_role(&env, Role::FeeManager, &caller);
+
+        if bounty < 0 {
+            panic!("Bounty cannot be negative");
+        }
+
+        let key = DataKey::KeeperPool(token.clone());
+        let mut pool: KeeperPool = env.storage().instance().get(&key).unwrap_or(KeeperPool { balance: 0, bounty: 0 });
+        pool.bounty = bounty;
+        env.storage().instance().set(&key, &pool);
+
+        env.events().publish((symbol_short!("kp_bounty"), token), bounty);
+    }
+
+    fn get_keeper_pool(env: Env, token: Address) -> Option<KeeperPool> {
+        env.storage().instance().get(&DataKey::KeeperPool(token))
+    }
+
+    fn set_protocol_fee(env: Env, caller: Address, fee_bps: u32, treasury: Address) {
+        require_role(&env, Role::FeeManager, &caller);
+
+        if fee_bps > 10000 {
+            panic!("Invalid fee config");
+        }
+
+        env.storage().instance().set(&DataKey::ProtocolFee, &ProtocolFee { fee_bps, treasury: treasury.clone() });
+
+        env.events().publish((symbol_short!("fee_set"), treasury), fee_bps);
+    }
+
+    fn get_protocol_fee(env: Env) -> Option<ProtocolFee> {
+        env.storage().instance().get(&DataKey::ProtocolFee)
+    }
+
+    fn set_creator_bond(env: Env, caller: Address, bond_bps: u32) {
+        require_role(&env, Role::FeeManager, &caller);
+
+        if bond_bps > 10000 {
+            panic!("Bond cannot exceed 100%");
+        }
+
+        env.storage().instance().set(&DataKey::CreatorBondBps, &bond_bps);
+
+        env.events().publish((symbol_short!("bond_set"),), bond_bps);
+    }
+
+    fn get_creator_bond_bps(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::CreatorBondBps).unwrap_or(0)
+    }
+
+    fn advance_round(env: Env, keeper: Address, circle_id: u64) -> i128 {
+        keeper.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        require_not_paused(&env, &mut circle);
+
+        // Paying a round out early is fine, but only a round that has reached its due time
+        // earns a bounty, so members who all pay at once cannot cycle a circle for rewards
+        let round = current_round(&circle);
+        let was_due = env.ledger().timestamp() >= circle.deadline_timestamp;
+        let (_, pot) = pay_out_round(&env, circle_id, &mut circle);
+        if !was_due {
+            return 0;
+        }
+
+        pay_keeper_bounty(&env, circle_id, &circle, round, KeeperAction::Advance, pot, &keeper)
+    }
+
+    fn settle_round(env: Env, keeper: Address, circle_id: u64) -> i128 {
+        keeper.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        require_not_paused(&env, &mut circle);
+
+        if env.ledger().timestamp() <= circle.deadline_timestamp + grace_period(&env, circle_id) {
+            panic!("Round deadline has not passed");
+        }
+
+        // Guarantees cover every unpaid member they can; insurance then covers the first
+        // one it can afford, once per round
+        let mut covered = false;
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+        for address in members.iter() {
+            let mut member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address)).unwrap();
+            if !member.is_active || circle.contribution_bitmap & (1 << member.index) != 0 {
+                continue;
+            }
+            if draw_guarantee(&env, circle_id, &mut circle, &mut member) {
+                covered = true;
+            } else if !circle.is_insurance_used && circle.insurance_balance >= circle.contribution_amount {
+                cover_with_insurance(&env, circle_id, &mut circle, &mut member);
+                covered = true;
+            }
+        }
+
+        if !covered {
+            panic!("Nothing to settle");
+        }
+
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+        let pot = circle.contribution_amount * circle.member_count as i128;
+        pay_keeper_bounty(&env, circle_id, &circle, current_round(&circle), KeeperAction::Settle, pot, &keeper)
+    }
+
+    fn prepay(env: Env, user: Address, circle_id: u64, rounds: u32) {
+        user.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        require_not_paused(&env, &mut circle);
+
+        let member = require_circle_member(&env, circle_id, &user);
+
+        // Credit can never cover more rounds than the circle has left
+        let per_round = math::round_due(circle.contribution_amount, circle.insurance_fee_bps);
+        let credit_key = DataKey::Credit(circle_id, user.clone());
+        let credit: i128 = env.storage().instance().get(&credit_key).unwrap_or(0);
+        let paid_this_round = circle.contribution_bitmap & (1 << member.index) != 0;
+        let rounds_left = circle.max_members - circle.payout_bitmap.count_ones() - paid_this_round as u32;
+        let credited_rounds = if per_round > 0 { (credit / per_round) as u32 } else { 0 };
+        if rounds == 0 || credited_rounds + rounds > rounds_left {
+            panic!("Invalid number of rounds to prepay");
+        }
+
+        // A current round already past its deadline also needs its late fee covered
+        let mut amount = per_round * rounds as i128;
+        if !paid_this_round {
+            amount += owed_partial(&env, circle_id, &user, &circle).late_fee;
+        }
+        let client = token::Client::new(&env, &circle.token);
+        client.transfer(&user, &env.current_contract_address(), &amount);
+
+        env.storage().instance().set(&credit_key, &(credit + amount));
+
+        // Cover the current round straight away if it is still unpaid
+        apply_credits(&env, circle_id, &mut circle);
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+        env.events().publish((symbol_short!("prepaid"), circle_id, user), amount);
+    }
+
+    fn get_credit(env: Env, circle_id: u64, user: Address) -> i128 {
+        env.storage().instance().get(&DataKey::Credit(circle_id, user)).unwrap_or(0)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_circle(env: Env, creator: Address, amount: i128, max_members: u32, token: Address, cycle_duration: u64, insurance_fee_bps: u32, nft_contract: Option<Address>) -> u64 {
+        // The creator signs for the circle and its bond
+        creator.require_auth();
+
+        // 1. Get the current Circle Count
+        let mut circle_count: u64 = env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0);
+        
+        // 2. Increment the ID for the new circle
+        circle_count += 1;
+
+        if max_members > 64 {
+            panic!("Max members cannot exceed 64 for optimization");
+        }
+
+        if amount < 0 {
+            panic!("Contribution amount cannot be negative");
+        }
+
+        // Only admin-approved tokens, within their limits
+        let token_config: TokenConfig = env.storage().instance().get(&DataKey::Token(token.clone()))
+            .unwrap_or_else(|| panic!("Token is not on the allowlist"));
+
+        if !token_config.is_listed {
+            panic!("Token is not on the allowlist");
+        }
+
+        if amount < token_config.min_contribution || amount > token_config.max_contribution {
+            panic!("Contribution amount outside token limits");
+        }
+
+        let max_tvl = amount.checked_mul(max_members as i128).unwrap_or(i128::MAX);
+        if max_tvl > token_config.max_circle_tvl {
+            panic!("Circle exceeds token TVL cap");
+        }
+
+        if insurance_fee_bps > 10000 {
+            panic!("Insurance fee cannot exceed 100%");
+        }
+
+        // 3. Create the Circle Data Struct
+        let current_time = env.ledger().timestamp();
+        let new_circle = CircleInfo {
+            id: circle_count,
+            creator: creator.clone(),
+            contribution_amount: amount,
+            max_members,
+            member_count: 0,
+            current_recipient_index: 0,
+            status: CircleStatus::Active,
+            token,
+            deadline_timestamp: current_time + cycle_duration,
+            cycle_duration,
+            contribution_bitmap: 0,
+            payout_bitmap: 0,
+            insurance_balance: 0,
+            insurance_fee_bps,
+            is_insurance_used: false,
+            late_fee_bps: 100, // Default 1%
+            proposed_late_fee_bps: 0,
+            proposal_votes_bitmap: 0,
+            dissolution_votes_bitmap: 0,
+            nft_contract,
+            paused_at: 0,
+            paused_until: 0,
+            pause_time_used: 0,
+            protocol_pause_offset: get_pause_state(&env).total_paused_time,
+        };
+
+        // 4. Save the Circle and the new Count
+        env.storage().instance().set(&DataKey::Circle(circle_count), &new_circle);
+        env.storage().instance().set(&DataKey::CircleCount, &circle_count);
+
+        // Lock the creator bond; it comes back when the circle completes
+        let bond_amount = math::creator_bond(amount, max_members, Self::get_creator_bond_bps(env.clone()));
+        if bond_amount > 0 {
+            let client = token::Client::new(&env, &new_circle.token);
+            client.transfer(&creator, &env.current_contract_address(), &bond_amount);
+        }
+        let bond = CreatorBond { creator: creator.clone(), amount: bond_amount, status: BondStatus::Locked };
+        env.storage().instance().set(&DataKey::Bond(circle_count), &bond);
+
+        // 5. Return the new ID
+        circle_count
+    }
+
+    fn join_circle(env: Env, user: Address, circle_id: u64) {
+        // 1. Authorization: The user MUST sign this transaction
+        user.require_auth();
+
+        admit_member(&env, circle_id, user, None);
+    }
+
+    fn join_with_guarantor(env: Env, user: Address, circle_id: u64, guarantor: Address, guarantee: i128) {
+        // Both the newcomer and the guarantor sign
+        user.require_auth();
+        guarantor.require_auth();
+
+        admit_member(&env, circle_id, user.clone(), Some((guarantor.clone(), guarantee)));
+
+        env.events().publish((symbol_short!("guar_join"), circle_id, user), (guarantor, guarantee));
+    }
+
+    fn set_guarantor_threshold(env: Env, caller: Address, circle_id: u64, min_score: i128) {
+        caller.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can set the bar for joining
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can set the guarantor threshold");
+        }
+        if min_score < 0 {
+            panic!("Threshold cannot be negative");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("guar_min"), min_score)) {
+            return;
+        }
+
+        env.storage().instance().set(&DataKey::GuarantorThreshold(circle_id), &min_score);
+    }
+
+    fn get_guarantee(env: Env, circle_id: u64, user: Address) -> Option<Guarantee> {
+        env.storage().instance().get(&DataKey::Guarantee(circle_id, user))
+    }
+
+    fn get_reputation(env: Env, user: Address, token: Address) -> Reputation {
+        env.storage().persistent().get(&DataKey::Reputation(user, token)).unwrap_or_default()
+    }
+
+    fn deposit(env: Env, user: Address, circle_id: u64, amount: i128) {
+        // 1. Authorization: The user must sign this!
+        user.require_auth();
+
+        deposit_from(&env, circle_id, &user, amount, DepositSource::Wallet(user.clone()));
+    }
+
+    fn deposit_for(env: Env, payer: Address, member: Address, circle_id: u64) {
+        payer.require_auth();
+
+        // The round is credited to the member; the payer gets nothing back from the circle
+        let payment = deposit_from(&env, circle_id, &member, i128::MAX, DepositSource::Wallet(payer.clone()));
+
+        env.events().publish((symbol_short!("dep_for"), circle_id, member), (payer, payment));
+    }
+
+    fn set_delegate(env: Env, user: Address, circle_id: u64, delegate: Address, deposit_allowance: i128, can_vote: bool, expires_at: u64) {
+        user.require_auth();
+
+        require_circle_member(&env, circle_id, &user);
+
+        if delegate == user {
+            panic!("Cannot delegate to yourself");
+        }
+
+        if deposit_allowance < 0 {
+            panic!("Allowance cannot be negative");
+        }
+
+        let delegation = Delegation { deposit_allowance, can_vote, expires_at };
+        env.storage().instance().set(&DataKey::Delegate(circle_id, user.clone(), delegate.clone()), &delegation);
+
+        env.events().publish((symbol_short!("dlg_set"), circle_id, user), (delegate, deposit_allowance, can_vote));
+    }
+
+    fn revoke_delegate(env: Env, user: Address, circle_id: u64, delegate: Address) {
+        user.require_auth();
+
+        let key = DataKey::Delegate(circle_id, user.clone(), delegate.clone());
+        if !env.storage().instance().has(&key) {
+            panic!("Not a delegate");
+        }
+        env.storage().instance().remove(&key);
+
+        env.events().publish((symbol_short!("dlg_rev"), circle_id, user), delegate);
+    }
+
+    fn get_delegate(env: Env, circle_id: u64, user: Address, delegate: Address) -> Option<Delegation> {
+        env.storage().instance().get(&DataKey::Delegate(circle_id, user, delegate))
+    }
+
+    fn delegate_deposit(env: Env, delegate: Address, member: Address, circle_id: u64) {
+        let key = DataKey::Delegate(circle_id, member.clone(), delegate.clone());
+        let mut delegation = require_delegate(&env, &key, &delegate);
+
+        // The member's token allowance still applies on top of the delegation limit
+        let outstanding = Self::get_outstanding(env.clone(), circle_id, member.clone());
+        if outstanding > delegation.deposit_allowance {
+            panic!("Delegate allowance exceeded");
+        }
+
+        let payment = deposit_from(&env, circle_id, &member, outstanding, DepositSource::MemberAllowance);
+        delegation.deposit_allowance -= payment;
+        env.storage().instance().set(&key, &delegation);
+    }
+
+    fn delegate_vote(env: Env, delegate: Address, member: Address, circle_id: u64, vote: MemberVote) {
+        let key = DataKey::Delegate(circle_id, member.clone(), delegate.clone());
+        let delegation = require_delegate(&env, &key, &delegate);
+
+        if !delegation.can_vote {
+            panic!("Delegate cannot vote");
+        }
+
+        match vote {
+            MemberVote::PenaltyChange => cast_penalty_vote(&env, member, circle_id),
+            MemberVote::Dissolution => cast_dissolution_vote(&env, member, circle_id),
+            MemberVote::RemoveCreator => cast_removal_vote(&env, member, circle_id),
+            MemberVote::ReplaceOrganiser(organiser, replacement) => cast_replacement_vote(&env, member, circle_id, organiser, replacement),
+        }
+    }
+
+    fn claim(env: Env, user: Address, circle_id: u64) -> i128 {
+        user.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        let key = DataKey::Claimable(circle_id, user.clone());
+        let claimable: Claimable = env.storage().instance().get(&key).unwrap_or_else(|| panic!("Nothing to claim"));
+        env.storage().instance().remove(&key);
+
+        let client = token::Client::new(&env, &circle.token);
+        client.transfer(&env.current_contract_address(), &user, &claimable.amount);
+
+        env.events().publish((symbol_short!("claimed"), circle_id, user), claimable.amount);
+
+        claimable.amount
+    }
+
+    fn set_pull_payouts(env: Env, user: Address, circle_id: u64, enabled: bool) {
+        user.require_auth();
+
+        let key = DataKey::PullPayouts(circle_id, user);
+        if enabled {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+    }
+
+    fn get_claimable(env: Env, circle_id: u64, user: Address) -> Option<Claimable> {
+        env.storage().instance().get(&DataKey::Claimable(circle_id, user))
+    }
+
+    fn recover_unclaimed(env: Env, caller: Address, circle_id: u64, user: Address) -> i128 {
+        require_admin(&env, &caller);
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        let key = DataKey::Claimable(circle_id, user.clone());
+        let claimable: Claimable = env.storage().instance().get(&key).unwrap_or_else(|| panic!("Nothing to claim"));
+
+        if env.ledger().timestamp() <= claimable.credited_at + CLAIM_TIMEOUT {
+            panic!("Claim timeout not reached");
+        }
+
+        let protocol_fee: ProtocolFee = env.storage().instance().get(&DataKey::ProtocolFee)
+            .unwrap_or_else(|| panic!("No treasury set"));
+        env.storage().instance().remove(&key);
+
+        let client = token::Client::new(&env, &circle.token);
+        client.transfer(&env.current_contract_address(), &protocol_fee.treasury, &claimable.amount);
+
+        env.events().publish((symbol_short!("recovered"), circle_id, user), claimable.amount);
+
+        claimable.amount
+    }
+
+    fn set_payout_split(env: Env, user: Address, circle_id: u64, shares: Vec<SplitShare>) {
+        user.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, user.clone()))
+            .unwrap_or_else(|| panic!("User is not a member of this circle"));
+
+        if !member.is_active {
+            panic!("Member is ejected");
+        }
+
+        if circle.payout_bitmap & (1 << member.index) != 0 {
+            panic!("Payout already received");
+        }
+
+        // No redirecting a pot once the round that pays it has begun, whether by the
+        // clock or because the previous round paid out early
+        let schedule = Self::get_schedule(env.clone(), circle_id);
+        for round in schedule.iter() {
+            let started = round.round <= current_round(&circle) || env.ledger().timestamp() + circle.cycle_duration >= round.due_time;
+            if round.recipient == Some(user.clone()) && started {
+                panic!("Payout split is locked");
+            }
+        }
+
+        let key = DataKey::PayoutSplit(circle_id, user.clone());
+        if shares.is_empty() {
+            env.storage().instance().remove(&key);
+            return;
+        }
+
+        if shares.len() > MAX_SPLIT_SHARES {
+            panic!("Too many split shares");
+        }
+        let mut total: u32 = 0;
+        for (i, share) in shares.iter().enumerate() {
+            if share.bps == 0 {
+                panic!("Split share cannot be zero");
+            }
+            for other in shares.slice(i as u32 + 1..).iter() {
+                if other.to == share.to {
+                    panic!("Duplicate split destination");
+                }
+            }
+            total = total.saturating_add(share.bps);
+        }
+        if total != 10000 {
+            panic!("Split shares must total 10000");
+        }
+
+        env.storage().instance().set(&key, &shares);
+
+        env.events().publish((symbol_short!("split"), circle_id, user), shares.len());
+    }
+
+    fn get_payout_split(env: Env, circle_id: u64, user: Address) -> Vec<SplitShare> {
+        env.storage().instance().get(&DataKey::PayoutSplit(circle_id, user)).unwrap_or(Vec::new(&env))
+    }
+
+    fn get_outstanding(env: Env, circle_id: u64, user: Address) -> i128 {
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, user.clone()))
+            .unwrap_or_else(|| panic!("User is not a member of this circle"));
+
+        if !member.is_active || circle.status != CircleStatus::Active || circle.contribution_bitmap & (1 << member.index) != 0 {
+            return 0;
+        }
+
+        outstanding_for(&env, circle_id, &circle, &member)
+    }
+
+    fn set_grace_period(env: Env, caller: Address, circle_id: u64, grace_period: u64) {
+        caller.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can change the grace window
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can set the grace period");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("grace"), grace_period)) {
+            return;
+        }
+
+        // A grace window longer than a cycle would overlap the next round
+        if grace_period >= circle.cycle_duration {
+            panic!("Grace period must be shorter than the cycle");
+        }
+
+        env.storage().instance().set(&DataKey::GracePeriod(circle_id), &grace_period);
+    }
+
+    fn get_schedule(env: Env, circle_id: u64) -> Vec<ScheduledRound> {
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+
+        // Members already paid held the earlier rounds, in rotation order;
+        // active members still waiting take the rounds that follow
+        let mut recipients: Vec<Address> = Vec::new(&env);
+        let mut unlisted = 0;
+        for address in members.iter() {
+            match env.storage().instance().get::<DataKey, Member>(&DataKey::Member(circle_id, address.clone())) {
+                Some(member) if circle.payout_bitmap & (1 << member.index) != 0 => recipients.push_back(address),
+                Some(_) => {}
+                // No member record: the slot keeps its round but has no known recipient
+                None => unlisted += 1,
+            }
+        }
+        for address in members.iter() {
+            if let Some(member) = env.storage().instance().get::<DataKey, Member>(&DataKey::Member(circle_id, address.clone())) {
+                if member.is_active && circle.payout_bitmap & (1 << member.index) == 0 {
+                    recipients.push_back(address);
+                }
+            }
+        }
+
+        // Open slots and slots without a record still get a round each
+        let total_rounds = recipients.len() + unlisted + (circle.max_members - members.len());
+
+        let round = current_round(&circle);
+        let mut schedule = Vec::new(&env);
+        for n in 0..total_rounds {
+            // Every round is one cycle apart, anchored on the current round's due time
+            let due_time = if n >= round {
+                circle.deadline_timestamp + (n - round) as u64 * circle.cycle_duration
+            } else {
+                circle.deadline_timestamp - (round - n) as u64 * circle.cycle_duration
+            };
+            schedule.push_back(ScheduledRound {
+                round: n,
+                due_time,
+                recipient: recipients.get(n),
+            });
+        }
+        schedule
+    }
+
+    fn was_paid_on_time(env: Env, circle_id: u64, round: u32, user: Address) -> bool {
+        match Self::get_deposit_receipt(env, circle_id, round, user) {
+            Some(receipt) => !receipt.late,
+            None => false,
+        }
+    }
+
+    fn get_deposit_receipt(env: Env, circle_id: u64, round: u32, user: Address) -> Option<DepositReceipt> {
+        env.storage().persistent().get(&DataKey::Deposit(circle_id, round, user))
+    }
+
+    fn set_auto_debit(env: Env, user: Address, circle_id: u64, enabled: bool) {
+        user.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, user.clone()))
+            .unwrap_or_else(|| panic!("User is not a member of this circle"));
+
+        if !member.is_active {
+            panic!("Member is ejected");
+        }
+
+        let key = DataKey::AutoDebit(circle_id, user.clone());
+        if enabled {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+
+        env.events().publish((symbol_short!("autodebit"), circle_id, user), enabled);
+    }
+
+    fn collect_due(env: Env, circle_id: u64) -> CollectionReport {
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        require_not_paused(&env, &mut circle);
+
+        let contract = env.current_contract_address();
+        let client = token::Client::new(&env, &circle.token);
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+
+        let mut report = CollectionReport {
+            collected: Vec::new(&env),
+            skipped: Vec::new(&env),
+        };
+
+        for address in members.iter() {
+            if !env.storage().instance().get(&DataKey::AutoDebit(circle_id, address.clone())).unwrap_or(false) {
+                continue;
+            }
+
+            let mut member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address.clone())).unwrap();
+            if !member.is_active || circle.contribution_bitmap & (1 << member.index) != 0 {
+                continue;
+            }
+
+            let outstanding = outstanding_for(&env, circle_id, &circle, &member);
+
+            // One short member must not fail the whole batch
+            if client.allowance(&address, &contract) < outstanding || client.balance(&address) < outstanding {
+                report.skipped.push_back(address);
+                continue;
+            }
+
+            // A transfer can still fail, e.g. from a frozen or deauthorised balance
+            if outstanding > 0 && !matches!(client.try_transfer_from(&contract, &address, &contract, &outstanding), Ok(Ok(()))) {
+                report.skipped.push_back(address);
+                continue;
+            }
+            record_payment(&env, circle_id, &mut circle, &mut member, outstanding);
+            report.collected.push_back(address);
+        }
+
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+        env.events().publish((symbol_short!("collected"), circle_id), (report.collected.len(), report.skipped.len()));
+
+        report
+    }
+
+    fn trigger_insurance_coverage(env: Env, caller: Address, circle_id: u64, member: Address) {
+        caller.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can trigger insurance
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can trigger insurance");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("insure"), member.clone(), current_round(&circle))) {
+            return;
+        }
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        require_not_paused(&env, &mut circle);
+
+        let member_key = DataKey::Member(circle_id, member.clone());
+        let mut member_info: Member = env.storage().instance().get(&member_key).unwrap();
+
+        if !member_info.is_active {
+            panic!("Member is ejected");
+        }
+
+        // Mark member as contributed in the bitmap
+        if (circle.contribution_bitmap & (1 << member_info.index)) != 0 {
+            panic!("Member already contributed");
+        }
+
+        // A guarantee is drawn before the insurance fund is touched
+        if !draw_guarantee(&env, circle_id, &mut circle, &mut member_info) {
+            // Check if insurance was already used this cycle
+            if circle.is_insurance_used {
+                panic!("Insurance already used this cycle");
+            }
+
+            // Check if there is enough balance
+            if circle.insurance_balance < circle.contribution_amount {
+                panic!("Insufficient insurance balance");
+            }
+
+            cover_with_insurance(&env, circle_id, &mut circle, &mut member_info);
+        }
+
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+    }
+
+    fn propose_penalty_change(env: Env, user: Address, circle_id: u64, new_bps: u32) {
+        user.require_auth();
+        
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+        
+        // Check if user is a member
+        let member = require_circle_member(&env, circle_id, &user);
+
+        if new_bps > 10000 {
+            panic!("Penalty cannot exceed 100%");
+        }
+
+        // Set proposal
         circle.proposed_late_fee_bps = new_bps;
         circle.proposal_votes_bitmap = 0;
         
-        // Auto-vote for proposer
-        circle.proposal_votes_bitmap |= 1 << member.index;
+        // Auto-vote for proposer
+        circle.proposal_votes_bitmap |= 1 << member.index;
+
+        // Check for immediate majority (e.g. 1 member circle)
+        if circle.proposal_votes_bitmap.count_ones() > (circle.member_count / 2) {
+            circle.late_fee_bps = circle.proposed_late_fee_bps;
+            circle.proposed_late_fee_bps = 0;
+            circle.proposal_votes_bitmap = 0;
+        }
+
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+    }
+
+    fn vote_penalty_change(env: Env, user: Address, circle_id: u64) {
+        user.require_auth();
+        cast_penalty_vote(&env, user, circle_id);
+    }
+
+    fn eject_member(env: Env, caller: Address, circle_id: u64, member: Address) {
+        caller.require_auth();
+        
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+        
+        // Only organisers can eject
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can eject members");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("eject"), member.clone())) {
+            return;
+        }
+
+        // Ejecting from a finished circle would burn a completed member's receipt
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        let member_key = DataKey::Member(circle_id, member.clone());
+        let mut member_info: Member = env.storage().instance().get(&member_key).expect("Member not found");
+
+        if !member_info.is_active {
+            panic!("Member already ejected");
+        }
+
+        // Mark as inactive
+        member_info.is_active = false;
+        env.storage().instance().set(&member_key, &member_info);
+
+        // Return any prepaid rounds and unfinished instalments
+        let credit_key = DataKey::Credit(circle_id, member.clone());
+        let credit: i128 = env.storage().instance().get(&credit_key).unwrap_or(0);
+        let partial = get_partial(&env, circle_id, &member, &circle);
+        let refund = credit + partial.paid;
+        pay_member(&env, circle_id, &circle.token, &member, refund);
+        release_guarantee(&env, circle_id, &circle, &member);
+        env.storage().instance().remove(&credit_key);
+        env.storage().instance().remove(&DataKey::Partial(circle_id, member.clone()));
+
+        // Burn NFT
+        call_nft(&env, circle_id, &circle, member_info.index, &member, NftAction::Burn);
+    }
+
+    fn dissolve_circle(env: Env, caller: Address, circle_id: u64) {
+        caller.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can dissolve unilaterally
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can dissolve the circle");
+        }
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("dissolve"),)) {
+            return;
+        }
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        // Once someone has been paid, the remaining members must agree
+        if circle.payout_bitmap != 0 {
+            panic!("Payouts have started: dissolution requires a member vote");
+        }
+
+        dissolve(&env, circle_id, &mut circle);
+    }
+
+    fn vote_dissolution(env: Env, user: Address, circle_id: u64) {
+        user.require_auth();
+        cast_dissolution_vote(&env, user, circle_id);
+    }
+
+    fn vote_remove_creator(env: Env, user: Address, circle_id: u64) {
+        user.require_auth();
+        cast_removal_vote(&env, user, circle_id);
+    }
+
+    fn report_abandonment(env: Env, circle_id: u64) {
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+        require_creator_in_office(&env, circle_id);
+
+        // Pause time is credited to the deadline first, so a paused circle is never abandoned
+        require_not_paused(&env, &mut circle);
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+        // Members falling behind is not the creator's doing. Only a round every active
+        // member has paid for, then left unpaid for cycles on end, counts; the clock
+        // starts at the due time plus grace, or at the last payment if that came later.
+        let mut funded_at = circle.deadline_timestamp + grace_period(&env, circle_id);
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+        for address in members.iter() {
+            let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address)).unwrap();
+            if !member.is_active {
+                continue;
+            }
+            if circle.contribution_bitmap & (1 << member.index) == 0 {
+                panic!("Circle is not abandoned");
+            }
+            funded_at = funded_at.max(member.last_contribution_time);
+        }
+        if env.ledger().timestamp() <= funded_at + ABANDONMENT_CYCLES * circle.cycle_duration {
+            panic!("Circle is not abandoned");
+        }
+
+        slash_bond(&env, circle_id);
+    }
+
+    fn get_creator_bond(env: Env, circle_id: u64) -> Option<CreatorBond> {
+        env.storage().instance().get(&DataKey::Bond(circle_id))
+    }
+
+    fn set_organisers(env: Env, caller: Address, circle_id: u64, organisers: Vec<Address>, threshold: u32) {
+        caller.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        // Only organisers can reshape the organiser set
+        if !is_organiser(&env, circle_id, &circle, &caller) {
+            panic!("Unauthorized: Only organisers can change the organisers");
+        }
+
+        if organisers.is_empty() || organisers.len() > MAX_ORGANISERS {
+            panic!("Organisers must number between 1 and 16");
+        }
+        if threshold == 0 || threshold > organisers.len() {
+            panic!("Threshold must be between 1 and the number of organisers");
+        }
+        for (i, organiser) in organisers.iter().enumerate() {
+            if organisers.first_index_of(&organiser) != Some(i as u32) {
+                panic!("Duplicate organiser");
+            }
+        }
+
+        if !approve_action(&env, circle_id, &circle, &caller, (symbol_short!("organise"), organisers.clone(), threshold)) {
+            return;
+        }
+
+        let current = get_organisers(&env, circle_id, &circle);
+        let updated = Organisers { members: organisers, threshold, epoch: current.epoch + 1 };
+        env.storage().instance().set(&DataKey::Organisers(circle_id), &updated);
+
+        env.events().publish((symbol_short!("org_set"), circle_id), (updated.members, threshold));
+    }
+
+    fn get_organisers(env: Env, circle_id: u64) -> Organisers {
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap_or_else(|| panic!("Circle not found"));
+        get_organisers(&env, circle_id, &circle)
+    }
+
+    fn propose_creator(env: Env, caller: Address, circle_id: u64, new_creator: Address) {
+        caller.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if caller != circle.creator {
+            panic!("Unauthorized: Only creator can hand over the circle");
+        }
+        require_creator_in_office(&env, circle_id);
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        env.storage().instance().set(&DataKey::PendingCreator(circle_id), &new_creator);
+
+        env.events().publish((symbol_short!("crt_prop"), circle_id), new_creator);
+    }
+
+    fn accept_creator(env: Env, new_creator: Address, circle_id: u64) {
+        new_creator.require_auth();
+
+        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        let pending_key = DataKey::PendingCreator(circle_id);
+        let pending: Address = env.storage().instance().get(&pending_key)
+            .unwrap_or_else(|| panic!("No pending creator"));
+        if pending != new_creator {
+            panic!("Unauthorized: Not the pending creator");
+        }
+        require_creator_in_office(&env, circle_id);
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        // The newcomer posts the same bond and the outgoing creator gets theirs back
+        let old_creator = circle.creator.clone();
+        let bond_key = DataKey::Bond(circle_id);
+        let bond: Option<CreatorBond> = env.storage().instance().get(&bond_key);
+        if let Some(mut bond) = bond {
+            if bond.amount > 0 {
+                let client = token::Client::new(&env, &circle.token);
+                client.transfer(&new_creator, &env.current_contract_address(), &bond.amount);
+                pay_member(&env, circle_id, &circle.token, &bond.creator, bond.amount);
+            }
+            bond.creator = new_creator.clone();
+            env.storage().instance().set(&bond_key, &bond);
+        }
+
+        // The new creator takes the old one's organiser seat
+        let mut organisers = get_organisers(&env, circle_id, &circle);
+        if let Some(i) = organisers.members.first_index_of(&old_creator) {
+            if organisers.members.contains(&new_creator) {
+                organisers.members.remove(i);
+                organisers.threshold = organisers.threshold.min(organisers.members.len());
+            } else {
+                organisers.members.set(i, new_creator.clone());
+            }
+            organisers.epoch += 1;
+            env.storage().instance().set(&DataKey::Organisers(circle_id), &organisers);
+        }
+
+        circle.creator = new_creator.clone();
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+        env.storage().instance().remove(&pending_key);
+
+        env.events().publish((symbol_short!("crt_acpt"), circle_id), (old_creator, new_creator));
+    }
+
+    fn get_pending_creator(env: Env, circle_id: u64) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingCreator(circle_id))
+    }
+
+    fn vote_replace_organiser(env: Env, user: Address, circle_id: u64, organiser: Address, replacement: Address) {
+        user.require_auth();
+        cast_replacement_vote(&env, user, circle_id, organiser, replacement);
+    }
+
+    fn set_recovery_guardians(env: Env, user: Address, circle_id: u64, guardians: Vec<Address>, threshold: u32) {
+        user.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+        if !members.contains(&user) {
+            panic!("User is not a member of this circle");
+        }
+
+        let key = DataKey::RecoveryGuardians(circle_id, user.clone());
+        if guardians.is_empty() {
+            env.storage().instance().remove(&key);
+            return;
+        }
+
+        if guardians.len() > MAX_GUARDIANS {
+            panic!("Too many guardians");
+        }
+        if threshold == 0 || threshold > guardians.len() {
+            panic!("Threshold must be between 1 and the number of guardians");
+        }
+        for (i, guardian) in guardians.iter().enumerate() {
+            if guardian == user {
+                panic!("Cannot guard yourself");
+            }
+            if guardians.first_index_of(&guardian) != Some(i as u32) {
+                panic!("Duplicate guardian");
+            }
+        }
+
+        env.storage().instance().set(&key, &RecoveryGuardians { guardians, threshold });
+    }
+
+    fn get_recovery_guardians(env: Env, circle_id: u64, user: Address) -> Option<RecoveryGuardians> {
+        env.storage().instance().get(&DataKey::RecoveryGuardians(circle_id, user))
+    }
+
+    fn recover_slot(env: Env, caller: Address, circle_id: u64, old: Address, new: Address) {
+        caller.require_auth();
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+        if !members.contains(&old) {
+            panic!("User is not a member of this circle");
+        }
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, old.clone())).unwrap();
+        if !member.is_active {
+            panic!("Member is ejected");
+        }
+        if old == new || holds_active_slot(&env, &new) {
+            panic!("New address is already a member");
+        }
+        if env.storage().instance().has(&DataKey::PendingRecovery(circle_id, old.clone())) {
+            panic!("Recovery already pending");
+        }
+
+        // The caller approves as one of the member's guardians, as another member, or both
+        let guardians: Option<RecoveryGuardians> = env.storage().instance().get(&DataKey::RecoveryGuardians(circle_id, old.clone()));
+        let approvals_key = DataKey::RecoveryApprovals(circle_id, old.clone(), new.clone());
+        let mut approvals: RecoveryApprovals = env.storage().instance().get(&approvals_key)
+            .unwrap_or(RecoveryApprovals { members: 0, guardians: Vec::new(&env) });
+
+        let mut counted = false;
+        if let Some(guardians) = &guardians {
+            if guardians.guardians.contains(&caller) {
+                if !approvals.guardians.contains(&caller) {
+                    approvals.guardians.push_back(caller.clone());
+                }
+                counted = true;
+            }
+        }
+        if caller != old && members.contains(&caller) {
+            let voter: Member = env.storage().instance().get(&DataKey::Member(circle_id, caller.clone())).unwrap();
+            if voter.is_active {
+                approvals.members |= 1 << voter.index;
+                counted = true;
+            }
+        }
+        if !counted {
+            panic!("Unauthorized: Only other members or guardians can approve a recovery");
+        }
+
+        // Guardians removed since they approved no longer count
+        let guardians_agree = match &guardians {
+            Some(guardians) => approvals.guardians.iter().filter(|g| guardians.guardians.contains(g)).count() as u32 >= guardians.threshold,
+            None => false,
+        };
+        let members_agree = approvals.members.count_ones() > (circle.member_count - 1) / 2;
+
+        if !guardians_agree && !members_agree {
+            env.storage().instance().set(&approvals_key, &approvals);
+            env.events().publish((symbol_short!("rec_vote"), circle_id, old), (new, caller));
+            return;
+        }
+
+        // The old key gets RECOVERY_DELAY to cancel a recovery it did not ask for
+        env.storage().instance().remove(&approvals_key);
+        let executable_at = env.ledger().timestamp() + RECOVERY_DELAY;
+        let pending = PendingRecovery { new: new.clone(), executable_at };
+        env.storage().instance().set(&DataKey::PendingRecovery(circle_id, old.clone()), &pending);
+
+        env.events().publish((symbol_short!("rec_sched"), circle_id, old), (new, executable_at));
+    }
+
+    fn execute_recovery(env: Env, circle_id: u64, old: Address) {
+        let key = DataKey::PendingRecovery(circle_id, old.clone());
+        let pending: PendingRecovery = env.storage().instance().get(&key)
+            .unwrap_or_else(|| panic!("No pending recovery"));
+        if env.ledger().timestamp() < pending.executable_at {
+            panic!("Recovery timelock has not passed");
+        }
+
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+        if circle.status != CircleStatus::Active {
+            panic!("Circle is not active");
+        }
+
+        // The slot and the new address may have changed while the recovery waited
+        let member = require_circle_member(&env, circle_id, &old);
+        if holds_active_slot(&env, &pending.new) {
+            panic!("New address is already a member");
+        }
+
+        env.storage().instance().remove(&key);
+        move_slot(&env, circle_id, &circle, member, &pending.new);
+
+        env.events().publish((symbol_short!("recovered"), circle_id), (old, pending.new));
+    }
+
+    fn cancel_recovery(env: Env, user: Address, circle_id: u64) {
+        user.require_auth();
+
+        let key = DataKey::PendingRecovery(circle_id, user.clone());
+        if !env.storage().instance().has(&key) {
+            panic!("No pending recovery");
+        }
+        env.storage().instance().remove(&key);
+
+        env.events().publish((symbol_short!("rec_cncl"), circle_id), user);
+    }
+
+    fn get_pending_recovery(env: Env, circle_id: u64, user: Address) -> Option<PendingRecovery> {
+        env.storage().instance().get(&DataKey::PendingRecovery(circle_id, user))
+    }
+
+    fn retry_nft(env: Env, circle_id: u64, slot: u32) -> bool {
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+        let key = DataKey::PendingNft(circle_id, slot);
+        let pending: Vec<PendingNft> = env.storage().instance().get(&key).unwrap_or(Vec::new(&env));
+
+        // The circle may have dropped its NFT contract since the calls failed
+        let nft_contract = match &circle.nft_contract {
+            Some(nft_contract) => nft_contract.clone(),
+            None => {
+                env.storage().instance().remove(&key);
+                return true;
+            }
+        };
+
+        // Replay in order, stopping at the first call that still fails
+        let mut done = 0;
+        for entry in pending.iter() {
+            if !try_nft(&env, &nft_contract, circle_id, slot, &entry.holder, &entry.action) {
+                break;
+            }
+            done += 1;
+        }
+
+        let remaining = pending.slice(done..);
+        if remaining.is_empty() {
+            env.storage().instance().remove(&key);
+        } else {
+            env.storage().instance().set(&key, &remaining);
+        }
+        remaining.is_empty()
+    }
+
+    fn get_pending_nft(env: Env, circle_id: u64, slot: u32) -> Vec<PendingNft> {
+        env.storage().instance().get(&DataKey::PendingNft(circle_id, slot)).unwrap_or(Vec::new(&env))
+    }
+
+    fn get_round_record(env: Env, circle_id: u64, round: u32) -> Option<RoundRecord> {
+        env.storage().persistent().get(&DataKey::RoundRecord(circle_id, round))
+    }
+
+    fn list_round_records(env: Env, circle_id: u64, start: u32, limit: u32) -> Vec<RoundRecord> {
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap_or_else(|| panic!("Circle not found"));
+        let limit = limit.min(MAX_PAGE_SIZE);
+
+        let mut records = Vec::new(&env);
+        for round in start..current_round(&circle) {
+            if records.len() >= limit {
+                break;
+            }
+            if let Some(record) = Self::get_round_record(env.clone(), circle_id, round) {
+                records.push_back(record);
+            }
+        }
+        records
+    }
+
+    fn get_circle(env: Env, circle_id: u64) -> CircleInfo {
+        env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap_or_else(|| panic!("Circle not found"))
+    }
+
+    fn get_member(env: Env, circle_id: u64, user: Address) -> Member {
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+        if !members.contains(&user) {
+            panic!("User is not a member of this circle");
+        }
+
+        // Dissolution clears member records
+        env.storage().instance().get(&DataKey::Member(circle_id, user)).unwrap_or_else(|| panic!("User is not a member of this circle"))
+    }
+
+    fn get_members(env: Env, circle_id: u64) -> Vec<Address> {
+        if !env.storage().instance().has(&DataKey::Circle(circle_id)) {
+            panic!("Circle not found");
+        }
+        env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env))
+    }
+
+    fn get_contribution_status(env: Env, circle_id: u64) -> Map<Address, bool> {
+        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap_or_else(|| panic!("Circle not found"));
+        let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(&env));
+
+        let mut status = Map::new(&env);
+        for address in members.iter() {
+            if let Some(member) = env.storage().instance().get::<_, Member>(&DataKey::Member(circle_id, address.clone())) {
+                // Ejected members no longer owe anything
+                if member.is_active {
+                    status.set(address, circle.contribution_bitmap & (1 << member.index) != 0);
+                }
+            }
+        }
+        status
+    }
+
+    fn list_circles(env: Env, start: u64, limit: u32, status: Option<CircleStatus>, token: Option<Address>) -> (Vec<CircleInfo>, u64) {
+        let circle_count: u64 = env.storage().instance().get(&DataKey::CircleCount).unwrap_or(0);
+
+        // IDs start at 1. The page bounds the IDs scanned, not the matches, so a filter
+        // that matches nothing still costs one page per call.
+        let first = start.max(1);
+        let last = circle_count.min(first.saturating_add(limit.min(MAX_PAGE_SIZE) as u64) - 1);
+        let next = if last < circle_count { last + 1 } else { 0 };
+        let mut circles = Vec::new(&env);
+        for circle_id in first..=last {
+            let circle: CircleInfo = match env.storage().instance().get(&DataKey::Circle(circle_id)) {
+                Some(circle) => circle,
+                None => continue,
+            };
+            if status.as_ref().is_some_and(|status| *status != circle.status) {
+                continue;
+            }
+            if token.as_ref().is_some_and(|token| *token != circle.token) {
+                continue;
+            }
+            circles.push_back(circle);
+        }
+        (circles, next)
+    }
+}
+
+// --- INTERNAL HELPERS ---
+
+fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    if role == Role::ProtocolAdmin {
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        return admin.as_ref() == Some(account);
+    }
+    env.storage().instance().get(&DataKey::Role(role, account.clone())).unwrap_or(false)
+}
+
+// Requires the caller's signature and that they hold the given role
+fn require_role(env: &Env, role: Role, caller: &Address) {
+    caller.require_auth();
+    if !has_role(env, role, caller) {
+        panic!("Unauthorized: Missing required role");
+    }
+}
+
+fn require_admin(env: &Env, caller: &Address) {
+    require_role(env, Role::ProtocolAdmin, caller);
+}
+
+// Converts one version 1 circle and its members. Version 1 kept no member list and keyed
+// member records by address alone, so the upgrader supplies the addresses in slot order;
+// each must still hold a version 1 record with that slot. Converted records move to the
+// circle's own key, which also stops an address being claimed by two circles.
+fn migrate_circle_v1(env: &Env, circle_id: u64, members: &Vec<Address>) {
+    let key = DataKey::Circle(circle_id);
+    let old: CircleInfoV1 = env.storage().instance().get(&key).unwrap_or_else(|| panic!("Circle not found"));
+
+    if members.len() != old.member_count {
+        panic!("Member list does not match the circle");
+    }
+
+    for (index, address) in members.iter().enumerate() {
+        let legacy_key = DataKeyV1::Member(address.clone());
+        let old_member: MemberV1 = env.storage().instance().get(&legacy_key)
+            .unwrap_or_else(|| panic!("Member list does not match the circle"));
+        if old_member.index != index as u32 {
+            panic!("Member list does not match the circle");
+        }
+
+        // Version 1 only counted deposits, each of one full contribution, and never paid out
+        let member = Member {
+            address: old_member.address,
+            index: old_member.index,
+            contribution_count: old_member.contribution_count,
+            last_contribution_time: old_member.last_contribution_time,
+            is_active: old_member.is_active,
+            total_contributed: old_member.contribution_count as i128 * old.contribution_amount as i128,
+            total_received: 0,
+            insurance_debt: 0,
+        };
+        env.storage().instance().remove(&legacy_key);
+        env.storage().instance().set(&DataKey::Member(circle_id, address.clone()), &member);
+        set_history(env, &DataKey::MemberCircle(address), &circle_id);
+    }
+    env.storage().instance().set(&DataKey::CircleMembers(circle_id), members);
+
+    let circle = CircleInfo {
+        id: old.id,
+        creator: old.creator,
+        contribution_amount: old.contribution_amount as i128,
+        max_members: old.max_members,
+        member_count: old.member_count,
+        current_recipient_index: old.current_recipient_index,
+        // Version 1 only cleared is_active to shut a circle down
+        status: if old.is_active { CircleStatus::Active } else { CircleStatus::Dissolved },
+        token: old.token,
+        deadline_timestamp: old.deadline_timestamp,
+        cycle_duration: old.cycle_duration,
+        contribution_bitmap: old.contribution_bitmap,
+        payout_bitmap: old.payout_bitmap,
+        insurance_balance: old.insurance_balance as i128,
+        insurance_fee_bps: old.insurance_fee_bps,
+        is_insurance_used: old.is_insurance_used,
+        late_fee_bps: old.late_fee_bps,
+        proposed_late_fee_bps: old.proposed_late_fee_bps,
+        proposal_votes_bitmap: old.proposal_votes_bitmap,
+        dissolution_votes_bitmap: 0,
+        nft_contract: Some(old.nft_contract),
+        paused_at: 0,
+        paused_until: 0,
+        pause_time_used: 0,
+        protocol_pause_offset: 0,
+    };
+    env.storage().instance().set(&key, &circle);
+}
+
+// Version 1 kept one reserve counter across every circle and token, and never collected
+// the fees it counted, so there are no tokens behind it to split between circles
+fn migrate_reserve_v1(env: &Env) {
+    env.storage().instance().remove(&DataKeyV1::GroupReserve);
+}
+
+fn grace_period(env: &Env, circle_id: u64) -> u64 {
+    env.storage().instance().get(&DataKey::GracePeriod(circle_id)).unwrap_or(0)
+}
+
+fn insurance_fee(circle: &CircleInfo) -> i128 {
+    math::insurance_fee(circle.contribution_amount, circle.insurance_fee_bps)
+}
+
+// Rounds are numbered by how many payouts have been made
+fn current_round(circle: &CircleInfo) -> u32 {
+    circle.payout_bitmap.count_ones()
+}
+
+// What a member still owes for the current round (contribution plus insurance fee)
+fn outstanding_for(env: &Env, circle_id: u64, circle: &CircleInfo, member: &Member) -> i128 {
+    let partial = owed_partial(env, circle_id, &member.address, circle);
+    circle.contribution_amount + insurance_fee(circle) + partial.late_fee - partial.paid
+}
+
+// The member's instalments this round, with the late fee assessed if the round is now
+// past its due time plus grace. The fee applies once, to whatever was still outstanding
+// at the deadline, and is owed on top of the round's dues.
+fn owed_partial(env: &Env, circle_id: u64, user: &Address, circle: &CircleInfo) -> PartialPayment {
+    let mut partial = get_partial(env, circle_id, user, circle);
+    let is_late = env.ledger().timestamp() > circle.deadline_timestamp + grace_period(env, circle_id);
+    if is_late && !partial.penalized {
+        let due = circle.contribution_amount + insurance_fee(circle);
+        partial.late_fee = math::late_fee(due - partial.paid, circle.late_fee_bps);
+        partial.penalized = true;
+    }
+    partial
+}
+
+// Books a payment the contract has already received towards the member's current round.
+// The round is paid once the running total covers contribution, insurance fee and any
+// late fee; the late fee then goes to the circle's reserve.
+// Saves the member; the caller saves the circle.
+fn record_payment(env: &Env, circle_id: u64, circle: &mut CircleInfo, member: &mut Member, payment: i128) {
+    let insurance_fee = insurance_fee(circle);
+    let partial_key = DataKey::Partial(circle_id, member.address.clone());
+    let mut partial = owed_partial(env, circle_id, &member.address, circle);
+    let due = circle.contribution_amount + insurance_fee + partial.late_fee;
+
+    member.last_contribution_time = env.ledger().timestamp();
+    partial.paid += payment;
+
+    if partial.paid < due {
+        // Still short: keep the running total for this round
+        env.storage().instance().set(&partial_key, &partial);
+        env.storage().instance().set(&DataKey::Member(circle_id, member.address.clone()), member);
+        return;
+    }
+
+    // Round fully paid: record the contribution
+    env.storage().instance().remove(&partial_key);
+
+    if partial.late_fee > 0 {
+        let penalties_key = DataKey::RoundPenalties(circle_id, current_round(circle));
+        let penalties: i128 = env.storage().instance().get(&penalties_key).unwrap_or(0);
+        env.storage().instance().set(&penalties_key, &(penalties + partial.late_fee));
+
+        let reserve_key = DataKey::GroupReserve(circle_id);
+        let reserve: i128 = env.storage().instance().get(&reserve_key).unwrap_or(0);
+        env.storage().instance().set(&reserve_key, &(reserve + partial.late_fee));
+    }
+
+    if insurance_fee > 0 {
+        circle.insurance_balance += insurance_fee;
+    }
+
+    member.contribution_count += 1;
+    member.total_contributed += circle.contribution_amount;
+    env.storage().instance().set(&DataKey::Member(circle_id, member.address.clone()), member);
+
+    // On time means the whole round was covered before the due time plus grace
+    write_receipt(env, circle_id, circle, &member.address, partial.penalized);
+    circle.contribution_bitmap |= 1 << member.index;
+}
+
+fn write_receipt(env: &Env, circle_id: u64, circle: &CircleInfo, user: &Address, late: bool) {
+    let receipt = DepositReceipt {
+        amount: math::round_due(circle.contribution_amount, circle.insurance_fee_bps),
+        paid_at: env.ledger().timestamp(),
+        late,
+    };
+    set_history(env, &DataKey::Deposit(circle_id, current_round(circle), user.clone()), &receipt);
+}
+
+// Receipts and round records grow with every round, and per-address records with every
+// user, so they are kept in persistent storage as entries of their own rather than in
+// the instance entry every call loads
+fn set_history<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(key, HISTORY_TTL_THRESHOLD, HISTORY_TTL);
+}
+
+// Instalments paid towards the current round; anything from an earlier round is ignored
+fn get_partial(env: &Env, circle_id: u64, user: &Address, circle: &CircleInfo) -> PartialPayment {
+    let round = current_round(circle);
+    let stored: Option<PartialPayment> = env.storage().instance().get(&DataKey::Partial(circle_id, user.clone()));
+    match stored {
+        Some(partial) if partial.round == round => partial,
+        _ => PartialPayment { round, paid: 0, penalized: false, late_fee: 0 },
+    }
+}
+
+// Pays the current round out of prepaid credit for every active member who has
+// enough credit and has not paid yet. Credit is booked like a deposit made now, so
+// credit that covers a round after its deadline is late and carries the late fee.
+fn apply_credits(env: &Env, circle_id: u64, circle: &mut CircleInfo) {
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+
+    for address in members.iter() {
+        let credit_key = DataKey::Credit(circle_id, address.clone());
+        let credit: i128 = env.storage().instance().get(&credit_key).unwrap_or(0);
+        if credit == 0 {
+            continue;
+        }
+
+        let member_key = DataKey::Member(circle_id, address.clone());
+        let mut member: Member = env.storage().instance().get(&member_key).unwrap();
+        if !member.is_active || circle.contribution_bitmap & (1 << member.index) != 0 {
+            continue;
+        }
+
+        // Credit tops up any instalments already paid this round
+        let outstanding = outstanding_for(env, circle_id, circle, &member);
+        if credit < outstanding {
+            continue;
+        }
+
+        record_payment(env, circle_id, circle, &mut member, outstanding);
+        env.storage().instance().set(&credit_key, &(credit - outstanding));
+    }
+}
+
+// Returns leftover credit once a circle has finished
+fn refund_credits(env: &Env, circle_id: u64, circle: &CircleInfo) {
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+
+    for address in members.iter() {
+        let credit_key = DataKey::Credit(circle_id, address.clone());
+        let credit: i128 = env.storage().instance().get(&credit_key).unwrap_or(0);
+        if credit > 0 {
+            pay_member(env, circle_id, &circle.token, &address, credit);
+            env.storage().instance().remove(&credit_key);
+        }
+    }
+}
+
+// Makes every remaining receipt transferable once the circle completes, and upgrades
+// the receipts of members the insurance fund never had to cover.
+fn release_nfts(env: &Env, circle_id: u64, circle: &CircleInfo) {
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+
+    for address in members.iter() {
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address.clone())).unwrap();
+        if !member.is_active {
+            continue;
+        }
+        call_nft(env, circle_id, circle, member.index, &address, NftAction::Unlock);
+        let guarantee: Option<Guarantee> = env.storage().instance().get(&DataKey::Guarantee(circle_id, address.clone()));
+        let guarantee_drawn = guarantee.map(|g| g.drawn).unwrap_or(0);
+        if member.insurance_debt == 0 && guarantee_drawn == 0 {
+            call_nft(env, circle_id, circle, member.index, &address, NftAction::AwardBadge);
+        }
+    }
+}
+
+// Credits every member who saw the circle through and releases their guarantees. Only
+// what members paid in themselves counts, and a circle of one, where the member only
+// ever pays themselves, earns nothing.
+fn record_completions(env: &Env, circle_id: u64, circle: &CircleInfo) {
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+
+    for address in members.iter() {
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address.clone())).unwrap();
+        if !member.is_active {
+            continue;
+        }
+        if circle.member_count > 1 {
+            let guarantee: Option<Guarantee> = env.storage().instance().get(&DataKey::Guarantee(circle_id, address.clone()));
+            let guarantee_drawn = guarantee.map(|g| g.drawn).unwrap_or(0);
+
+            let key = DataKey::Reputation(address.clone(), circle.token.clone());
+            let mut reputation: Reputation = env.storage().persistent().get(&key).unwrap_or_default();
+            reputation.circles_completed += 1;
+            reputation.value_completed += member.total_contributed - member.insurance_debt - guarantee_drawn;
+            set_history(env, &key, &reputation);
+        }
+
+        release_guarantee(env, circle_id, circle, &address);
+    }
+}
+
+// Calls the circle's NFT contract without letting it block membership changes: a failed
+// call, or any call queued behind one, is recorded for retry_nft instead of reverting.
+fn call_nft(env: &Env, circle_id: u64, circle: &CircleInfo, slot: u32, holder: &Address, action: NftAction) {
+    let nft_contract = match &circle.nft_contract {
+        Some(nft_contract) => nft_contract,
+        None => return,
+    };
+
+    let key = DataKey::PendingNft(circle_id, slot);
+    let mut pending: Vec<PendingNft> = env.storage().instance().get(&key).unwrap_or(Vec::new(env));
+
+    // A burn cancels a mint that never went through
+    if action == NftAction::Burn {
+        if let Some(first) = pending.first() {
+            if matches!(first.action, NftAction::Mint(_)) && first.holder == *holder {
+                env.storage().instance().remove(&key);
+                return;
+            }
+        }
+    }
+
+    if pending.is_empty() && try_nft(env, nft_contract, circle_id, slot, holder, &action) {
+        return;
+    }
+
+    pending.push_back(PendingNft { holder: holder.clone(), action });
+    env.storage().instance().set(&key, &pending);
+    env.events().publish((symbol_short!("nft_fail"), circle_id), slot);
+}
+
+fn try_nft(env: &Env, nft_contract: &Address, circle_id: u64, slot: u32, holder: &Address, action: &NftAction) -> bool {
+    let client = SusuNftClient::new(env, nft_contract);
+    let token_id = encode_token_id(circle_id, slot);
+    match action {
+        NftAction::Mint(metadata) => matches!(client.try_mint(holder, &token_id, metadata), Ok(Ok(()))),
+        NftAction::Burn => matches!(client.try_burn(holder, &token_id), Ok(Ok(()))),
+        NftAction::Unlock => matches!(client.try_unlock(&token_id), Ok(Ok(()))),
+        NftAction::AwardBadge => matches!(client.try_award_badge(&token_id), Ok(Ok(()))),
+        NftAction::Recover(to) => matches!(client.try_recover(holder, to, &token_id), Ok(Ok(()))),
+    }
+}
+
+// Tokens the contract holds for a circle: unpaid contributions, the insurance fund and
+// collected late fees. Insurance-covered rounds count as contributions, having moved out of the fund.
+fn circle_held(env: &Env, circle_id: u64, circle: &CircleInfo) -> i128 {
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+    let reserve: i128 = env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0);
+    let mut held = circle.insurance_balance + reserve;
+    for address in members.iter() {
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address)).unwrap();
+        held += member.total_contributed - member.total_received;
+    }
+    held
+}
+
+// Current value of a circle's vault shares, yield included
+fn vault_value(env: &Env, strategy: &YieldStrategy) -> i128 {
+    if strategy.shares == 0 {
+        return 0;
+    }
+    let total_shares: i128 = env.storage().instance().get(&DataKey::VaultShares(strategy.vault.clone())).unwrap();
+    let balance = VaultClient::new(env, &strategy.vault).balance(&env.current_contract_address());
+    balance * strategy.shares / total_shares
+}
+
+fn vault_deposit(env: &Env, circle: &CircleInfo, strategy: &mut YieldStrategy, amount: i128) {
+    let contract = env.current_contract_address();
+    let vault = VaultClient::new(env, &strategy.vault);
+
+    // Shares are priced before the deposit so existing circles keep their yield
+    let shares_key = DataKey::VaultShares(strategy.vault.clone());
+    let total_shares: i128 = env.storage().instance().get(&shares_key).unwrap_or(0);
+    let balance = vault.balance(&contract);
+    let new_shares = if total_shares == 0 || balance == 0 { amount } else { amount * total_shares / balance };
+
+    // The vault pulls the tokens from this contract
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: circle.token.clone(),
+                fn_name: symbol_short!("transfer"),
+                args: (contract.clone(), strategy.vault.clone(), amount).into_val(env),
+            },
+            sub_invocations: Vec::new(env),
+        }),
+    ]);
+    vault.deposit(&contract, &amount);
+
+    env.storage().instance().set(&shares_key, &(total_shares + new_shares));
+    strategy.shares += new_shares;
+    strategy.principal += amount;
+}
+
+// Withdraws `amount` of the circle's vault value back into this contract,
+// reducing principal in proportion to the value withdrawn.
+fn vault_withdraw(env: &Env, strategy: &mut YieldStrategy, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    let value = vault_value(env, strategy);
+    let principal_out = strategy.principal * amount / value;
+    vault_redeem(env, strategy, amount);
+    strategy.principal -= principal_out;
+    if strategy.shares == 0 {
+        strategy.principal = 0;
+    }
+}
+
+// Withdraws everything the circle's vault position has earned above principal
+fn vault_harvest(env: &Env, strategy: &mut YieldStrategy) -> i128 {
+    let accrued = vault_value(env, strategy) - strategy.principal;
+    if accrued <= 0 {
+        return 0;
+    }
+    vault_redeem(env, strategy, accrued);
+    accrued
+}
+
+fn vault_redeem(env: &Env, strategy: &mut YieldStrategy, amount: i128) {
+    let contract = env.current_contract_address();
+    let vault = VaultClient::new(env, &strategy.vault);
+
+    let shares_key = DataKey::VaultShares(strategy.vault.clone());
+    let total_shares: i128 = env.storage().instance().get(&shares_key).unwrap();
+    let balance = vault.balance(&contract);
+    let value = balance * strategy.shares / total_shares;
+    if amount > value {
+        panic!("Insufficient vault balance");
+    }
+
+    // Round the burned shares up so other circles never subsidise this one
+    let burned = if amount == value {
+        strategy.shares
+    } else {
+        (amount * total_shares + balance - 1) / balance
+    };
+
+    vault.withdraw(&contract, &amount);
+
+    env.storage().instance().set(&shares_key, &(total_shares - burned));
+    strategy.shares -= burned;
+}
+
+fn get_pause_state(env: &Env) -> PauseState {
+    env.storage().instance().get(&DataKey::ProtocolPause).unwrap_or(PauseState {
+        is_paused: false,
+        paused_at: 0,
+        total_paused_time: 0,
+    })
+}
+
+// Pushes the circle deadline out by any pause time it has not yet been credited with,
+// so members are never charged late fees for time they could not pay in.
+fn sync_pause(env: &Env, circle: &mut CircleInfo) {
+    let state = get_pause_state(env);
+    if state.total_paused_time > circle.protocol_pause_offset {
+        circle.deadline_timestamp += state.total_paused_time - circle.protocol_pause_offset;
+        circle.protocol_pause_offset = state.total_paused_time;
+    }
+
+    // An expired circle pause ends on its own
+    if circle.paused_until != 0 && env.ledger().timestamp() >= circle.paused_until {
+        circle.deadline_timestamp += circle.paused_until - circle.paused_at;
+        circle.paused_at = 0;
+        circle.paused_until = 0;
+    }
+}
+
+// Guard for value-moving entrypoints. Refunds and dissolution deliberately skip it.
+fn require_not_paused(env: &Env, circle: &mut CircleInfo) {
+    if get_pause_state(env).is_paused {
+        panic!("Protocol is paused");
+    }
+    sync_pause(env, circle);
+    if circle.paused_until != 0 {
+        panic!("Circle is paused");
+    }
+}
+
+// Pays a member's round out of the insurance fund. The caller checks eligibility.
+fn cover_with_insurance(env: &Env, circle_id: u64, circle: &mut CircleInfo, member_info: &mut Member) {
+    circle.contribution_bitmap |= 1 << member_info.index;
+    circle.insurance_balance -= circle.contribution_amount;
+    circle.is_insurance_used = true;
+
+    // The covered round counts as paid in, but the member owes it back to the fund
+    member_info.total_contributed += circle.contribution_amount;
+    member_info.insurance_debt += circle.contribution_amount;
+    env.storage().instance().set(&DataKey::Member(circle_id, member_info.address.clone()), member_info);
+
+    carry_partial_to_credit(env, circle_id, circle, &member_info.address);
+    record_default(env, circle, &member_info.address, None);
+}
+
+// Pays a member's round out of their guarantee, if enough of it is left. The guarantor
+// stands behind the member, so nothing is owed back to the circle.
+fn draw_guarantee(env: &Env, circle_id: u64, circle: &mut CircleInfo, member_info: &mut Member) -> bool {
+    let key = DataKey::Guarantee(circle_id, member_info.address.clone());
+    let guarantee: Option<Guarantee> = env.storage().instance().get(&key);
+    let Some(mut guarantee) = guarantee else { return false };
+    if guarantee.amount - guarantee.drawn < circle.contribution_amount {
+        return false;
+    }
+
+    guarantee.drawn += circle.contribution_amount;
+    env.storage().instance().set(&key, &guarantee);
+
+    circle.contribution_bitmap |= 1 << member_info.index;
+    member_info.total_contributed += circle.contribution_amount;
+    env.storage().instance().set(&DataKey::Member(circle_id, member_info.address.clone()), member_info);
+
+    let guaranteed_key = DataKey::RoundGuaranteed(circle_id, current_round(circle));
+    let guaranteed: u64 = env.storage().instance().get(&guaranteed_key).unwrap_or(0);
+    env.storage().instance().set(&guaranteed_key, &(guaranteed | 1 << member_info.index));
+
+    carry_partial_to_credit(env, circle_id, circle, &member_info.address);
+    record_default(env, circle, &member_info.address, Some(&guarantee.guarantor));
+
+    env.events().publish((symbol_short!("guar_draw"), circle_id, member_info.address.clone()), circle.contribution_amount);
+    true
+}
+
+// Instalments already paid this round carry over as prepaid credit
+fn carry_partial_to_credit(env: &Env, circle_id: u64, circle: &CircleInfo, member: &Address) {
+    let partial = get_partial(env, circle_id, member, circle);
+    if partial.paid > 0 {
+        let credit_key = DataKey::Credit(circle_id, member.clone());
+        let credit: i128 = env.storage().instance().get(&credit_key).unwrap_or(0);
+        env.storage().instance().set(&credit_key, &(credit + partial.paid));
+    }
+    env.storage().instance().remove(&DataKey::Partial(circle_id, member.clone()));
+}
+
+// Counts a covered round against the member and, if one vouched for them, the guarantor
+fn record_default(env: &Env, circle: &CircleInfo, member: &Address, guarantor: Option<&Address>) {
+    let key = DataKey::Reputation(member.clone(), circle.token.clone());
+    let mut reputation: Reputation = env.storage().persistent().get(&key).unwrap_or_default();
+    reputation.defaults += 1;
+    reputation.value_defaulted += circle.contribution_amount;
+    set_history(env, &key, &reputation);
+
+    if let Some(guarantor) = guarantor {
+        let key = DataKey::Reputation(guarantor.clone(), circle.token.clone());
+        let mut reputation: Reputation = env.storage().persistent().get(&key).unwrap_or_default();
+        reputation.guaranteed_defaults += 1;
+        reputation.value_guaranteed_defaulted += circle.contribution_amount;
+        set_history(env, &key, &reputation);
+    }
+}
+
+// Value paid in across completed circles, less every default the address caused or vouched for
+fn reputation_score(env: &Env, user: &Address, token: &Address) -> i128 {
+    let reputation: Reputation = env.storage().persistent().get(&DataKey::Reputation(user.clone(), token.clone())).unwrap_or_default();
+    (reputation.value_completed - reputation.value_defaulted - reputation.value_guaranteed_defaulted).max(0)
+}
+
+fn guarantor_threshold(env: &Env, circle_id: u64) -> i128 {
+    env.storage().instance().get(&DataKey::GuarantorThreshold(circle_id)).unwrap_or(0)
+}
+
+// Hands back whatever is left of a member's guarantee
+fn release_guarantee(env: &Env, circle_id: u64, circle: &CircleInfo, member: &Address) {
+    let key = DataKey::Guarantee(circle_id, member.clone());
+    let guarantee: Option<Guarantee> = env.storage().instance().get(&key);
+    if let Some(guarantee) = guarantee {
+        pay_member(env, circle_id, &circle.token, &guarantee.guarantor, guarantee.amount - guarantee.drawn);
+        env.storage().instance().remove(&key);
+    }
+}
+
+// Pays the keeper its bounty from the pool for the circle token, at most once per
+// circle, round and action, and never more than a small slice of the round's pot.
+// A circle's bounties are also capped at the protocol fees it has paid, so spinning
+// up many circles to farm the pool costs at least what it earns. Circles cycling
+// faster than MIN_BOUNTY_CYCLE earn nothing. Returns the bounty paid.
+fn pay_keeper_bounty(env: &Env, circle_id: u64, circle: &CircleInfo, round: u32, action: KeeperAction, pot: i128, keeper: &Address) -> i128 {
+    if circle.cycle_duration < MIN_BOUNTY_CYCLE {
+        return 0;
+    }
+
+    let claim_key = DataKey::BountyClaim(circle_id, round, action);
+    if env.storage().instance().has(&claim_key) {
+        return 0;
+    }
+    env.storage().instance().set(&claim_key, &true);
+
+    let pool_key = DataKey::KeeperPool(circle.token.clone());
+    let mut pool: KeeperPool = match env.storage().instance().get(&pool_key) {
+        Some(pool) => pool,
+        None => return 0,
+    };
+
+    let budget_key = DataKey::BountyBudget(circle_id);
+    let budget: i128 = env.storage().instance().get(&budget_key).unwrap_or(0);
+    let bounty = math::keeper_bounty(pot, pool.bounty, pool.balance).min(budget);
+    if bounty <= 0 {
+        return 0;
+    }
+
+    pool.balance -= bounty;
+    env.storage().instance().set(&pool_key, &pool);
+    env.storage().instance().set(&budget_key, &(budget - bounty));
+
+    let client = token::Client::new(env, &circle.token);
+    client.transfer(&env.current_contract_address(), keeper, &bounty);
+
+    env.events().publish((symbol_short!("bounty"), circle_id, keeper.clone()), bounty);
+
+    bounty
+}
+
+// Pays the pot to the next recipient in the rotation and starts the next round.
+// Panics unless every active member has paid or been covered. Returns the recipient
+// and the gross pot.
+fn pay_out_round(env: &Env, circle_id: u64, circle: &mut CircleInfo) -> (Address, i128) {
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+
+    // Every active member must have paid (or been covered) this round
+    let mut active_mask = 0u64;
+    for address in members.iter() {
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address)).unwrap();
+        if member.is_active {
+            active_mask |= 1 << member.index;
+        }
+    }
+    if active_mask == 0 || circle.contribution_bitmap & active_mask != active_mask {
+        panic!("Not all members have contributed this round");
+    }
+
+    // Ejected members and members already paid are skipped in the rotation
+    let mut recipient: Option<Member> = None;
+    while circle.current_recipient_index < circle.member_count {
+        let address = members.get(circle.current_recipient_index).unwrap();
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address)).unwrap();
+        if member.is_active && circle.payout_bitmap & (1 << member.index) == 0 {
+            recipient = Some(member);
+            break;
+        }
+        circle.current_recipient_index += 1;
+    }
+    let mut recipient = recipient.unwrap_or_else(|| panic!("No member left to pay"));
+
+    let pot = math::pot(circle.contribution_amount, circle.contribution_bitmap.count_ones());
+    let mut payout = pot;
+
+    let strategy_key = DataKey::YieldStrategy(circle_id);
+    let strategy: Option<YieldStrategy> = env.storage().instance().get(&strategy_key);
+    if let Some(mut strategy) = strategy {
+        // Harvest yield, then pull back any principal the payout needs
+        let accrued = vault_harvest(env, &mut strategy);
+        if accrued > 0 {
+            match strategy.policy {
+                YieldPolicy::Distribute => payout += accrued,
+                YieldPolicy::Reserve => circle.insurance_balance += accrued,
+            }
+        }
+
+        let liquid = circle_held(env, circle_id, circle) - strategy.principal;
+        if liquid < pot {
+            let shortfall = (pot - liquid).min(vault_value(env, &strategy));
+            vault_withdraw(env, &mut strategy, shortfall);
+        }
+        env.storage().instance().set(&strategy_key, &strategy);
+    }
+
+    let client = token::Client::new(env, &circle.token);
+
+    // The protocol fee comes off the gross pot before the recipient is paid
+    let mut fee = 0;
+    let protocol_fee: Option<ProtocolFee> = env.storage().instance().get(&DataKey::ProtocolFee);
+    if let Some(protocol_fee) = protocol_fee {
+        fee = math::protocol_fee(pot, protocol_fee.fee_bps);
+        if fee > 0 {
+            payout -= fee;
+            client.transfer(&env.current_contract_address(), &protocol_fee.treasury, &fee);
+
+            let budget_key = DataKey::BountyBudget(circle_id);
+            let budget: i128 = env.storage().instance().get(&budget_key).unwrap_or(0);
+            env.storage().instance().set(&budget_key, &(budget + fee));
+        }
+    }
+
+    // A recipient who cannot take the token (no trustline, frozen balance) must not stall
+    // the round; their payout waits to be claimed
+    let split: Option<Vec<SplitShare>> = env.storage().instance().get(&DataKey::PayoutSplit(circle_id, recipient.address.clone()));
+    match split {
+        Some(shares) => {
+            // The last destination takes the rounding remainder
+            let mut remaining = payout;
+            for (i, share) in shares.iter().enumerate() {
+                let amount = if i as u32 == shares.len() - 1 { remaining } else { math::bps_of(payout, share.bps) };
+                remaining -= amount;
+                pay_member(env, circle_id, &circle.token, &share.to, amount);
+            }
+        }
+        None => pay_member(env, circle_id, &circle.token, &recipient.address, payout),
+    }
+
+    recipient.total_received += pot;
+    env.storage().instance().set(&DataKey::Member(circle_id, recipient.address.clone()), &recipient);
+
+    write_round_record(env, circle_id, circle, &members, &recipient.address, pot, payout, fee);
+
+    // Start the next round, due one cycle after this one however late the payout ran
+    circle.payout_bitmap |= 1 << recipient.index;
+    circle.contribution_bitmap = 0;
+    circle.is_insurance_used = false;
+    circle.current_recipient_index += 1;
+    circle.deadline_timestamp += circle.cycle_duration;
+
+    if circle.payout_bitmap & active_mask == active_mask {
+        circle.status = CircleStatus::Completed;
+        refund_credits(env, circle_id, circle);
+        release_nfts(env, circle_id, circle);
+        record_completions(env, circle_id, circle);
+        return_bond(env, circle_id);
+    } else {
+        apply_credits(env, circle_id, circle);
+    }
+
+    env.storage().instance().set(&DataKey::Circle(circle_id), circle);
+
+    env.events().publish((symbol_short!("payout"), circle_id), (recipient.address.clone(), payout));
+
+    (recipient.address, pot)
+}
+
+// Freezes the round's history before its bitmaps are cleared. Members who paid have a
+// receipt; contributors without one were covered by insurance.
+#[allow(clippy::too_many_arguments)]
+fn write_round_record(env: &Env, circle_id: u64, circle: &CircleInfo, members: &Vec<Address>, recipient: &Address, pot: i128, payout: i128, fee: i128) {
+    let round = current_round(circle);
+
+    // Slots covered without a receipt were paid by a guarantee or, failing that, insurance
+    let guaranteed_key = DataKey::RoundGuaranteed(circle_id, round);
+    let guarantee_covered: u64 = env.storage().instance().get(&guaranteed_key).unwrap_or(0);
+    env.storage().instance().remove(&guaranteed_key);
+
+    let mut late_payers = 0u64;
+    let mut insurance_covered = 0u64;
+    for (index, address) in members.iter().enumerate() {
+        if circle.contribution_bitmap & (1 << index) == 0 {
+            continue;
+        }
+        let receipt: Option<DepositReceipt> = env.storage().persistent().get(&DataKey::Deposit(circle_id, round, address));
+        match receipt {
+            Some(receipt) if receipt.late => late_payers |= 1 << index,
+            Some(_) => {}
+            None if guarantee_covered & (1 << index) == 0 => insurance_covered |= 1 << index,
+            None => {}
+        }
+    }
+
+    let penalties_key = DataKey::RoundPenalties(circle_id, round);
+    let penalties: i128 = env.storage().instance().get(&penalties_key).unwrap_or(0);
+    env.storage().instance().remove(&penalties_key);
+
+    let record = RoundRecord {
+        round,
+        due_time: circle.deadline_timestamp,
+        paid_out_at: env.ledger().timestamp(),
+        contributors: circle.contribution_bitmap,
+        late_payers,
+        insurance_covered,
+        guarantee_covered,
+        recipient: recipient.clone(),
+        gross_payout: pot,
+        net_payout: payout,
+        protocol_fee: fee,
+        penalties,
+    };
+    set_history(env, &DataKey::RoundRecord(circle_id, round), &record);
+}
+
+fn get_organisers(env: &Env, circle_id: u64, circle: &CircleInfo) -> Organisers {
+    env.storage().instance().get(&DataKey::Organisers(circle_id))
+        .unwrap_or_else(|| Organisers { members: vec![env, circle.creator.clone()], threshold: 1, epoch: 0 })
+}
+
+fn is_organiser(env: &Env, circle_id: u64, circle: &CircleInfo, caller: &Address) -> bool {
+    get_organisers(env, circle_id, circle).members.contains(caller)
+}
+
+// Records an organiser's sign-off on a privileged action and reports whether the
+// threshold is now met. Approvals are keyed by the action and its arguments, so
+// organisers must agree on exactly the same call.
+fn approve_action<A: IntoVal<Env, Val>>(env: &Env, circle_id: u64, circle: &CircleInfo, caller: &Address, action: A) -> bool {
+    let organisers = get_organisers(env, circle_id, circle);
+    if organisers.threshold <= 1 {
+        return true;
+    }
+
+    let action: Val = action.into_val(env);
+    let action_hash = env.crypto().sha256(&(circle_id, organisers.epoch, action).to_xdr(env)).to_bytes();
+    let key = DataKey::Approvals(circle_id, action_hash.clone());
+    let index = organisers.members.first_index_of(caller).unwrap();
+    let approvals: u32 = env.storage().instance().get::<_, u32>(&key).unwrap_or(0) | (1 << index);
+
+    if approvals.count_ones() < organisers.threshold {
+        env.storage().instance().set(&key, &approvals);
+        env.events().publish((symbol_short!("approve"), circle_id, caller.clone()), action_hash);
+        return false;
+    }
+    env.storage().instance().remove(&key);
+    true
+}
+
+// Whether an address has a member record in a circle that is still running. An address
+// holds one running slot at a time, so only the circle it last joined needs checking.
+fn holds_active_slot(env: &Env, user: &Address) -> bool {
+    let last_circle: Option<u64> = env.storage().persistent().get(&DataKey::MemberCircle(user.clone()));
+    match last_circle {
+        Some(id) => {
+            env.storage().instance().has(&DataKey::Member(id, user.clone()))
+                && env.storage().instance().get::<_, CircleInfo>(&DataKey::Circle(id)).unwrap().status == CircleStatus::Active
+        }
+        None => false,
+    }
+}
+
+// Hands a member's slot to a new address. The slot index, and with it every bitmap
+// position and the payout turn, stays the same.
+fn move_slot(env: &Env, circle_id: u64, circle: &CircleInfo, mut member: Member, new: &Address) {
+    let old = member.address.clone();
+    member.address = new.clone();
+    env.storage().instance().set(&DataKey::Member(circle_id, new.clone()), &member);
+    env.storage().instance().remove(&DataKey::Member(circle_id, old.clone()));
+    set_history(env, &DataKey::MemberCircle(new.clone()), &circle_id);
+    env.storage().persistent().remove(&DataKey::MemberCircle(old.clone()));
+
+    let members_key = DataKey::CircleMembers(circle_id);
+    let mut members: Vec<Address> = env.storage().instance().get(&members_key).unwrap();
+    let position = members.first_index_of(&old).unwrap();
+    members.set(position, new.clone());
+    env.storage().instance().set(&members_key, &members);
+
+    move_entry::<i128>(env, DataKey::Credit(circle_id, old.clone()), DataKey::Credit(circle_id, new.clone()));
+    move_entry::<PartialPayment>(env, DataKey::Partial(circle_id, old.clone()), DataKey::Partial(circle_id, new.clone()));
+    move_entry::<bool>(env, DataKey::AutoDebit(circle_id, old.clone()), DataKey::AutoDebit(circle_id, new.clone()));
+    move_entry::<bool>(env, DataKey::PullPayouts(circle_id, old.clone()), DataKey::PullPayouts(circle_id, new.clone()));
+    move_entry::<Vec<SplitShare>>(env, DataKey::PayoutSplit(circle_id, old.clone()), DataKey::PayoutSplit(circle_id, new.clone()));
+    move_entry::<Guarantee>(env, DataKey::Guarantee(circle_id, old.clone()), DataKey::Guarantee(circle_id, new.clone()));
+    move_entry::<RecoveryGuardians>(env, DataKey::RecoveryGuardians(circle_id, old.clone()), DataKey::RecoveryGuardians(circle_id, new.clone()));
+    for round in 0..=current_round(circle) {
+        let old_key = DataKey::Deposit(circle_id, round, old.clone());
+        let receipt: Option<DepositReceipt> = env.storage().persistent().get(&old_key);
+        if let Some(receipt) = receipt {
+            set_history(env, &DataKey::Deposit(circle_id, round, new.clone()), &receipt);
+            env.storage().persistent().remove(&old_key);
+        }
+    }
+
+    // The new address may already be owed something in this circle, e.g. as a split destination
+    let old_key = DataKey::Claimable(circle_id, old.clone());
+    let held: Option<Claimable> = env.storage().instance().get(&old_key);
+    if let Some(held) = held {
+        let new_key = DataKey::Claimable(circle_id, new.clone());
+        let mut claimable: Claimable = env.storage().instance().get(&new_key).unwrap_or(Claimable { amount: 0, credited_at: 0 });
+        claimable.amount += held.amount;
+        claimable.credited_at = claimable.credited_at.max(held.credited_at);
+        env.storage().instance().set(&new_key, &claimable);
+        env.storage().instance().remove(&old_key);
+    }
+
+    call_nft(env, circle_id, circle, member.index, &old, NftAction::Recover(new.clone()));
+}
+
+fn move_entry<V: IntoVal<Env, Val> + TryFromVal<Env, Val>>(env: &Env, from: DataKey, to: DataKey) {
+    let value: Option<V> = env.storage().instance().get(&from);
+    if let Some(value) = value {
+        env.storage().instance().set(&to, &value);
+        env.storage().instance().remove(&from);
+    }
+}
+
+// Adds a member to a circle, with the guarantor (and amount) who co-signed, if any.
+// Callers check the signatures.
+fn admit_member(env: &Env, circle_id: u64, user: Address, guarantor: Option<(Address, i128)>) {
+    // 2. Retrieve the circle data
+    let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+    if circle.status != CircleStatus::Active {
+        panic!("Circle is not active");
+    }
+
+    require_not_paused(env, &mut circle);
+
+    // 3. Check if the circle is full
+    if circle.member_count >= circle.max_members {
+        panic!("Circle is full");
+    }
+
+    // 4. Check if user is already a member to prevent duplicates. An address whose
+    // last circle has finished may join again.
+    let member_key = DataKey::Member(circle_id, user.clone());
+    if holds_active_slot(env, &user) {
+        panic!("User is already a member");
+    }
+
+    // Newcomers below the circle's bar need someone to vouch for them
+    let threshold = guarantor_threshold(env, circle_id);
+    match &guarantor {
+        None => {
+            if reputation_score(env, &user, &circle.token) < threshold {
+                panic!("A guarantor is required");
+            }
+        }
+        Some((guarantor, amount)) => {
+            if *guarantor == user {
+                panic!("Cannot guarantee yourself");
+            }
+            if reputation_score(env, guarantor, &circle.token) < threshold {
+                panic!("Guarantor reputation too low");
+            }
+            if *amount < 0 {
+                panic!("Guarantee cannot be negative");
+            }
+            if *amount > 0 {
+                let client = token::Client::new(env, &circle.token);
+                client.transfer(guarantor, &env.current_contract_address(), amount);
+            }
+            let guarantee = Guarantee { guarantor: guarantor.clone(), amount: *amount, drawn: 0 };
+            env.storage().instance().set(&DataKey::Guarantee(circle_id, user.clone()), &guarantee);
+        }
+    }
+
+    // 5. Create and store the new member
+    let new_member = Member {
+        address: user.clone(),
+        index: circle.member_count,
+        contribution_count: 0,
+        last_contribution_time: 0,
+        is_active: true,
+        total_contributed: 0,
+        total_received: 0,
+        insurance_debt: 0,
+    };
+    
+    // 6. Store the member and update circle count
+    env.storage().instance().set(&member_key, &new_member);
+    set_history(env, &DataKey::MemberCircle(user.clone()), &circle_id);
+    circle.member_count += 1;
+
+    let members_key = DataKey::CircleMembers(circle_id);
+    let mut members: Vec<Address> = env.storage().instance().get(&members_key).unwrap_or(Vec::new(env));
+    members.push_back(user.clone());
+    env.storage().instance().set(&members_key, &members);
+    
+    // 7. Save the updated circle back to storage
+    env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+    // 8. Mint Participation NFT
+    let metadata = NftMetadata {
+        circle_id,
+        slot: new_member.index,
+        token: circle.token.clone(),
+        contribution_amount: circle.contribution_amount,
+        joined_at: env.ledger().timestamp(),
+    };
+    call_nft(env, circle_id, &circle, new_member.index, &user, NftAction::Mint(metadata));
+}
+
+// Pays towards a member's current round and returns the amount taken. `amount` is capped
+// at what is still owed.
+fn deposit_from(env: &Env, circle_id: u64, user: &Address, amount: i128, source: DepositSource) -> i128 {
+    // 1. Load the Circle Data
+    let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+    if circle.status != CircleStatus::Active {
+        panic!("Circle is not active");
+    }
+
+    // Deadlines are shifted by any pause before lateness is judged
+    require_not_paused(env, &mut circle);
+
+    // 2. Check if user is actually a member of this circle
+    let mut member = require_circle_member(env, circle_id, user);
+
+    // One deposit per member per round; paying ahead goes through prepay
+    if circle.contribution_bitmap & (1 << member.index) != 0 {
+        panic!("Contribution already complete this round");
+    }
+
+    if amount < 0 {
+        panic!("Deposit amount cannot be negative");
+    }
+
+    // 3. Work out what is still owed this round (contribution plus insurance fee)
+    let outstanding = outstanding_for(env, circle_id, &circle, &member);
+
+    // Never pull more than is owed
+    let payment = amount.min(outstanding);
+    if payment == 0 && outstanding > 0 {
+        panic!("Deposit amount must be positive");
+    }
+
+    // 4. Transfer the instalment from the payer
+    if payment > 0 {
+        let client = token::Client::new(env, &circle.token);
+        let contract = env.current_contract_address();
+        match source {
+            DepositSource::Wallet(payer) => client.transfer(&payer, &contract, &payment),
+            DepositSource::MemberAllowance => client.transfer_from(&contract, user, &contract, &payment),
+        }
+    }
+
+    // 5. Record it against the round
+    record_payment(env, circle_id, &mut circle, &mut member, payment);
+    env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+
+    payment
+}
+
+// Checks the delegate's signature and that the delegation is still in force
+fn require_delegate(env: &Env, key: &DataKey, delegate: &Address) -> Delegation {
+    delegate.require_auth();
+
+    let delegation: Delegation = env.storage().instance().get(key).unwrap_or_else(|| panic!("Not a delegate"));
+    if delegation.expires_at != 0 && env.ledger().timestamp() > delegation.expires_at {
+        panic!("Delegation has expired");
+    }
+    delegation
+}
+
+// Loads a member's record, checking they hold an active slot in this circle
+fn require_circle_member(env: &Env, circle_id: u64, user: &Address) -> Member {
+    let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, user.clone()))
+        .unwrap_or_else(|| panic!("User is not a member of this circle"));
+    if !member.is_active {
+        panic!("Member is ejected");
+    }
+    member
+}
+
+// Member votes, cast by the member or by a delegate allowed to vote for them
+fn cast_penalty_vote(env: &Env, user: Address, circle_id: u64) {
+    let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    
+    // Check if user is a member
+    let member = require_circle_member(env, circle_id, &user);
+
+    if circle.proposed_late_fee_bps == 0 {
+        panic!("No active proposal");
+    }
+
+    circle.proposal_votes_bitmap |= 1 << member.index;
+
+    if circle.proposal_votes_bitmap.count_ones() > (circle.member_count / 2) {
+        circle.late_fee_bps = circle.proposed_late_fee_bps;
+        circle.proposed_late_fee_bps = 0;
+        circle.proposal_votes_bitmap = 0;
+    }
+
+    env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+}
+
+fn cast_dissolution_vote(env: &Env, user: Address, circle_id: u64) {
+    let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+    if circle.status != CircleStatus::Active {
+        panic!("Circle is not active");
+    }
+
+    // Check if user is a member
+    let member = require_circle_member(env, circle_id, &user);
+
+    circle.dissolution_votes_bitmap |= 1 << member.index;
+
+    if circle.dissolution_votes_bitmap.count_ones() > (circle.member_count / 2) {
+        dissolve(env, circle_id, &mut circle);
+    } else {
+        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+    }
+}
+
+fn cast_removal_vote(env: &Env, user: Address, circle_id: u64) {
+    let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+    if circle.status != CircleStatus::Active {
+        panic!("Circle is not active");
+    }
+    require_creator_in_office(env, circle_id);
+
+    let member = require_circle_member(env, circle_id, &user);
+
+    let votes_key = DataKey::CreatorRemovalVotes(circle_id);
+    let mut votes: u64 = env.storage().instance().get(&votes_key).unwrap_or(0);
+    votes |= 1 << member.index;
+
+    if votes.count_ones() > (circle.member_count / 2) {
+        env.storage().instance().remove(&votes_key);
+        slash_bond(env, circle_id);
+    } else {
+        env.storage().instance().set(&votes_key, &votes);
+    }
+}
+
+fn cast_replacement_vote(env: &Env, user: Address, circle_id: u64, organiser: Address, replacement: Address) {
+    let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+
+    if circle.status != CircleStatus::Active {
+        panic!("Circle is not active");
+    }
+
+    let member = require_circle_member(env, circle_id, &user);
+
+    // Only a sitting organiser, or the seat of a creator removed by vote, can be replaced
+    let mut organisers = get_organisers(env, circle_id, &circle);
+    let seat = organisers.members.first_index_of(&organiser);
+    let vacant: Option<Address> = env.storage().instance().get(&DataKey::VacantSeat(circle_id));
+    if seat.is_none() && vacant.as_ref() != Some(&organiser) {
+        panic!("Not an organiser of this circle");
+    }
+    if organisers.members.contains(&replacement) {
+        panic!("Replacement is already an organiser");
+    }
+
+    let votes_key = DataKey::OrganiserVotes(circle_id, organiser.clone(), replacement.clone());
+    let mut votes: u64 = env.storage().instance().get(&votes_key).unwrap_or(0);
+    votes |= 1 << member.index;
+
+    if votes.count_ones() <= (circle.member_count / 2) {
+        env.storage().instance().set(&votes_key, &votes);
+        return;
+    }
+    env.storage().instance().remove(&votes_key);
+
+    match seat {
+        Some(i) => organisers.members.set(i, replacement.clone()),
+        None => {
+            if organisers.members.len() >= MAX_ORGANISERS {
+                panic!("Organisers must number between 1 and 16");
+            }
+            organisers.members.push_back(replacement.clone());
+            env.storage().instance().remove(&DataKey::VacantSeat(circle_id));
+        }
+    }
+    organisers.threshold = organisers.threshold.max(1);
+    organisers.epoch += 1;
+    env.storage().instance().set(&DataKey::Organisers(circle_id), &organisers);
+
+    env.events().publish((symbol_short!("org_swap"), circle_id), (organiser, replacement));
+}
+
+// Sends circle funds to a member, or holds them as a claimable balance when the member
+// opted to pull or the transfer fails. One unreachable address never blocks the circle.
+// Funds are also held while a recovery of the address is pending, so they follow the
+// slot if the recovery goes ahead.
+fn pay_member(env: &Env, circle_id: u64, token: &Address, to: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+
+    let pull = env.storage().instance().get(&DataKey::PullPayouts(circle_id, to.clone())).unwrap_or(false);
+    let recovering = env.storage().instance().has(&DataKey::PendingRecovery(circle_id, to.clone()));
+    if !pull && !recovering {
+        let client = token::Client::new(env, token);
+        if let Ok(Ok(())) = client.try_transfer(&env.current_contract_address(), to, &amount) {
+            return;
+        }
+    }
+
+    let key = DataKey::Claimable(circle_id, to.clone());
+    let mut claimable: Claimable = env.storage().instance().get(&key).unwrap_or(Claimable { amount: 0, credited_at: 0 });
+    claimable.amount += amount;
+    claimable.credited_at = env.ledger().timestamp();
+    env.storage().instance().set(&key, &claimable);
+
+    env.events().publish((symbol_short!("held"), circle_id, to.clone()), amount);
+}
+
+// Creator powers end once the members have slashed the bond
+fn require_creator_in_office(env: &Env, circle_id: u64) {
+    let bond: Option<CreatorBond> = env.storage().instance().get(&DataKey::Bond(circle_id));
+    if let Some(bond) = bond {
+        if bond.status == BondStatus::Slashed {
+            panic!("Creator has been removed");
+        }
+    }
+}
+
+// Hands a locked bond back to the creator who posted it
+fn return_bond(env: &Env, circle_id: u64) {
+    let key = DataKey::Bond(circle_id);
+    let bond: Option<CreatorBond> = env.storage().instance().get(&key);
+    let Some(mut bond) = bond else { return };
+    if bond.status != BondStatus::Locked {
+        return;
+    }
+
+    let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    pay_member(env, circle_id, &circle.token, &bond.creator, bond.amount);
+    bond.status = BondStatus::Returned;
+    env.storage().instance().set(&key, &bond);
+}
+
+// Splits the bond evenly between the active members (the first takes any remainder)
+// and strips the creator of their powers.
+fn slash_bond(env: &Env, circle_id: u64) {
+    let key = DataKey::Bond(circle_id);
+    let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
+    let mut bond: CreatorBond = env.storage().instance().get(&key)
+        .unwrap_or(CreatorBond { creator: circle.creator.clone(), amount: 0, status: BondStatus::Locked });
+
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+    let mut active: Vec<Address> = Vec::new(env);
+    for address in members.iter() {
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address.clone())).unwrap();
+        if member.is_active {
+            active.push_back(address);
+        }
+    }
+    if active.is_empty() {
+        panic!("No members to compensate");
+    }
+
+    if bond.amount > 0 {
+        let share = bond.amount / active.len() as i128;
+        let remainder = bond.amount - share * active.len() as i128;
+        for (i, address) in active.iter().enumerate() {
+            let amount = if i == 0 { share + remainder } else { share };
+            pay_member(env, circle_id, &circle.token, &address, amount);
+        }
+    }
+    bond.status = BondStatus::Slashed;
+    env.storage().instance().set(&key, &bond);
+
+    // A removed creator gives up their organiser seat too
+    let mut organisers = get_organisers(env, circle_id, &circle);
+    if let Some(i) = organisers.members.first_index_of(&bond.creator) {
+        organisers.members.remove(i);
+        organisers.threshold = organisers.threshold.min(organisers.members.len()).max(1);
+        organisers.epoch += 1;
+        env.storage().instance().set(&DataKey::Organisers(circle_id), &organisers);
+        env.storage().instance().set(&DataKey::VacantSeat(circle_id), &bond.creator);
+    }
+
+    env.events().publish((symbol_short!("slashed"), circle_id), (bond.creator, bond.amount));
+}
+
+// Settles every member's net position, burns the participation NFTs and closes the circle.
+// Net position = deposits - payouts received - insurance debt + share of the insurance fund.
+// If members who were already paid out leave the pot short, the positive positions are
+// scaled down pro-rata so the circle never pays out more than it holds.
+fn dissolve(env: &Env, circle_id: u64, circle: &mut CircleInfo) {
+    let members: Vec<Address> = env.storage().instance().get(&DataKey::CircleMembers(circle_id)).unwrap_or(Vec::new(env));
+
+    // Bring invested funds home; any yield is shared like the insurance fund
+    let strategy_key = DataKey::YieldStrategy(circle_id);
+    let strategy: Option<YieldStrategy> = env.storage().instance().get(&strategy_key);
+    if let Some(mut strategy) = strategy {
+        if strategy.shares > 0 {
+            circle.insurance_balance += vault_harvest(env, &mut strategy);
+            let remaining = vault_value(env, &strategy);
+            vault_withdraw(env, &mut strategy, remaining);
+            env.storage().instance().set(&strategy_key, &strategy);
+        }
+    }
+
+    // Insurance debts are repaid into the fund before it is shared out, along with
+    // the late fees the circle collected
+    let reserve: i128 = env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0);
+    env.storage().instance().remove(&DataKey::GroupReserve(circle_id));
+    let mut active_count: i128 = 0;
+    let mut insurance_pool = circle.insurance_balance + reserve;
+    let mut held = circle.insurance_balance + reserve;
+    for address in members.iter() {
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address)).unwrap();
+        if member.is_active {
+            active_count += 1;
+        }
+        insurance_pool += member.insurance_debt;
+        held += member.total_contributed - member.total_received;
+    }
+    let insurance_share = if active_count > 0 { insurance_pool / active_count } else { 0 };
+
+    let mut positions: Vec<i128> = Vec::new(env);
+    let mut owed: i128 = 0;
+    for address in members.iter() {
+        let member: Member = env.storage().instance().get(&DataKey::Member(circle_id, address)).unwrap();
+        let mut position = member.total_contributed - member.total_received - member.insurance_debt;
+        if member.is_active {
+            position += insurance_share;
+        }
+        if position > 0 {
+            owed += position;
+        }
+        positions.push_back(position);
+    }
+
+    for (i, address) in members.iter().enumerate() {
+        let member_key = DataKey::Member(circle_id, address.clone());
+        let member: Member = env.storage().instance().get(&member_key).unwrap();
+
+        let position = positions.get(i as u32).unwrap();
+        let mut refund = math::pro_rata(position, held, owed);
+
+        // Prepaid credit and instalments are escrowed separately and always returned in full
+        let credit_key = DataKey::Credit(circle_id, address.clone());
+        refund += env.storage().instance().get::<_, i128>(&credit_key).unwrap_or(0);
+        refund += get_partial(env, circle_id, &address, circle).paid;
+        env.storage().instance().remove(&credit_key);
+        env.storage().instance().remove(&DataKey::Partial(circle_id, address.clone()));
+
+        pay_member(env, circle_id, &circle.token, &address, refund);
+        release_guarantee(env, circle_id, circle, &address);
+
+        // Ejected members already had their NFT burned
+        if member.is_active {
+            call_nft(env, circle_id, circle, member.index, &address, NftAction::Burn);
+        }
+
+        // Free the address so it can join another circle
+        env.storage().instance().remove(&member_key);
+    }
+
+    circle.insurance_balance = 0;
+    circle.dissolution_votes_bitmap = 0;
+    circle.status = CircleStatus::Dissolved;
+    env.storage().instance().set(&DataKey::Circle(circle_id), circle);
+
+    // Winding up early is not misconduct; the creator gets their bond back
+    return_bond(env, circle_id);
+}
+
+// --- FUZZ TESTING MODULES ---
+
+#[cfg(test)]
+mod fuzz_tests {
+    extern crate std;
+
+    use super::*;
+    use arbitrary::Arbitrary;
+    use soroban_sdk::testutils::{storage::Persistent, Address as TestAddress, Ledger};
+    use std::println;
+
+    #[contract]
+    pub struct MockNft;
+
+    #[contractimpl]
+    impl MockNft {
+        pub fn mint(env: Env, _to: Address, id: u128, metadata: NftMetadata) {
+            if env.storage().instance().has(&symbol_short!("broken")) {
+                panic!("NFT contract unavailable");
+            }
+            env.storage().instance().set(&id, &metadata);
+        }
+        pub fn burn(env: Env, _from: Address, id: u128) {
+            if env.storage().instance().has(&symbol_short!("broken")) {
+                panic!("NFT contract unavailable");
+            }
+            env.storage().instance().remove(&id);
+        }
+        pub fn unlock(_env: Env, _id: u128) {}
+        pub fn award_badge(env: Env, id: u128) {
+            env.storage().instance().set(&(id, symbol_short!("badge")), &true);
+        }
+        pub fn recover(env: Env, _from: Address, to: Address, id: u128) {
+            env.storage().instance().set(&(id, symbol_short!("owner")), &to);
+        }
+        // Test hooks: simulate an outage and read back what SoroSusu recorded
+        pub fn set_broken(env: Env, broken: bool) {
+            if broken {
+                env.storage().instance().set(&symbol_short!("broken"), &true);
+            } else {
+                env.storage().instance().remove(&symbol_short!("broken"));
+            }
+        }
+        pub fn metadata(env: Env, id: u128) -> Option<NftMetadata> {
+            env.storage().instance().get(&id)
+        }
+        pub fn has_badge(env: Env, id: u128) -> bool {
+            env.storage().instance().get(&(id, symbol_short!("badge"))).unwrap_or(false)
+        }
+        pub fn recovered_to(env: Env, id: u128) -> Option<Address> {
+            env.storage().instance().get(&(id, symbol_short!("owner")))
+        }
+    }
+
+    #[contract]
+    pub struct MockVault;
+
+    // Holds real tokens, so deposits and withdrawals move the circle's funds
+    #[contractimpl]
+    impl MockVault {
+        pub fn init(env: Env, token: Address) {
+            env.storage().instance().set(&symbol_short!("token"), &token);
+        }
+        pub fn deposit(env: Env, from: Address, amount: i128) {
+            let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+            token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+            let balance: i128 = env.storage().instance().get(&from).unwrap_or(0);
+            env.storage().instance().set(&from, &(balance + amount));
+        }
+        pub fn withdraw(env: Env, to: Address, amount: i128) {
+            let balance: i128 = env.storage().instance().get(&to).unwrap_or(0);
+            if amount > balance {
+                panic!("Insufficient balance");
+            }
+            env.storage().instance().set(&to, &(balance - amount));
+            let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+            token::Client::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+        }
+        pub fn balance(env: Env, owner: Address) -> i128 {
+            env.storage().instance().get(&owner).unwrap_or(0)
+        }
+        // Test hook: simulate yield accruing to an owner (the test mints the tokens)
+        pub fn accrue(env: Env, owner: Address, amount: i128) {
+            let balance: i128 = env.storage().instance().get(&owner).unwrap_or(0);
+            env.storage().instance().set(&owner, &(balance + amount));
+        }
+    }
+
+    #[allow(dead_code)]
+    #[derive(Arbitrary, Debug, Clone)]
+    pub struct FuzzTestCase {
+        pub contribution_amount: u64,
+        pub max_members: u16,
+        pub user_id: u64,
+    }
+
+    #[test]
+    fn fuzz_test_contribution_amount_edge_cases() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let nft_contract = env.register_contract(None, MockNft);
+
+        // Initialize contract
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Test case 1: Maximum u64 value (should not panic)
+        let max_circle_id = client.create_circle(
+            &creator,
+            &(u64::MAX as i128),
+            &10,
+            &token,
+            &604800, // 1 week in seconds
+            &0,
+            &Some(nft_contract.clone()),
+        );
+
+        let user1 = Address::generate(&env);
+        client.join_circle(&user1, &max_circle_id);
+
+        
+        // This should not panic even with u64::MAX contribution amount
+        token_admin_client.mint(&user1, &(u64::MAX as i128));
+        let result = client.try_deposit(&user1, &max_circle_id, &(u64::MAX as i128));
+
+        // The member holds enough, so nothing may overflow on the way
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn fuzz_test_zero_and_negative_amounts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let nft_contract = env.register_contract(None, MockNft);
+
+        // Initialize contract
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Test case 2: Zero contribution amount (should be allowed but may cause issues)
+        let zero_circle_id = client.create_circle(
+            &creator,
+            &0,
+            &10,
+            &token,
+            &604800, // 1 week in seconds
+            &0,
+            &Some(nft_contract.clone()),
+        );
+
+        let user2 = Address::generate(&env);
+        client.join_circle(&user2, &zero_circle_id);
+
+        // Zero amount deposit should work (though may not be practically useful)
+        let result = client.try_deposit(&user2, &zero_circle_id, &0);
+        
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn fuzz_test_arbitrary_contribution_amounts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let nft_contract = env.register_contract(None, MockNft);
+
+        // Initialize contract
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Test with various edge case amounts
+        let test_amounts = [
+            1,                           // Minimum positive amount
+            u32::MAX as i128,           // Large but reasonable amount
+            (u64::MAX / 2) as i128,     // Very large amount
+            (u64::MAX - 1) as i128,     // Maximum amount - 1
+            1000000,                    // 1 million
+            0,                          // Zero (already tested above)
+        ];
+
+        for amount in test_amounts.iter() {
+            let circle_id = client.create_circle(
+                &creator,
+                amount,
+                &10,
+                &token,
+                &604800, // 1 week in seconds
+                &0,
+                &Some(nft_contract.clone()),
+            );
+
+            let user = Address::generate(&env);
+            client.join_circle(&user, &circle_id);
+
+            // Should not fail due to overflow, only due to insufficient balance
+            assert!(*amount == 0 || client.try_deposit(&user, &circle_id, amount).is_err());
+            token_admin_client.mint(&user, amount);
+            let result = client.try_deposit(&user, &circle_id, amount);
+            assert!(result.is_ok(), "Amount {} failed: {:?}", amount, result);
+        }
+    }
+
+    #[test]
+    fn fuzz_test_boundary_conditions() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let nft_contract = env.register_contract(None, MockNft);
+
+        // Initialize contract
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Test boundary conditions for max_members
+        let boundary_tests = [
+            (1, "Minimum members"),
+            (64, "Maximum members"),
+            (50, "Typical circle size"),
+        ];
+
+        for (max_members, description) in boundary_tests {
+            let circle_id = client.create_circle(
+                &creator,
+                &1000, // Reasonable contribution amount
+                &max_members,
+                &token,
+                &604800, // 1 week in seconds
+                &0,
+                &Some(nft_contract.clone()),
+            );
+
+            // Test joining with maximum allowed members
+            for _ in 0..max_members.min(10) { // Limit to 10 for test performance
+                let user = Address::generate(&env);
+                client.join_circle(&user, &circle_id);
+                token_admin_client.mint(&user, &1000);
+                
+                let result = client.try_deposit(&user, &circle_id, &1000);
+                
+                assert!(result.is_ok(), "Deposit failed for {} with max_members {}: {:?}", description, max_members, result);
+            }
+            
+            println!("G�� Boundary test passed: {} (max_members: {})", description, max_members);
+        }
+    }
+
+    #[test]
+    fn fuzz_test_concurrent_deposits() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let nft_contract = env.register_contract(None, MockNft);
+
+        // Initialize contract
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &500,
+            &5,
+            &token,
+            &604800, // 1 week in seconds
+            &0,
+            &Some(nft_contract.clone()),
+        );
+
+        // Create multiple users and test deposits
+        let mut users = std::vec::Vec::new();
+        for _ in 0..5 {
+            let user = Address::generate(&env);
+            client.join_circle(&user, &circle_id);
+            token_admin_client.mint(&user, &500);
+            users.push(user);
+        }
+
+        // Test multiple deposits in sequence (simulating concurrent access)
+        for user in users {
+            let result = client.try_deposit(&user, &circle_id, &500);
+            
+            assert!(result.is_ok(), "Concurrent deposit test failed: {:?}", result);
+        }
+        
+        println!("G�� Concurrent deposits test passed");
+    }
+
+    #[test]
+    fn test_late_penalty_mechanism() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let nft_contract = env.register_contract(None, MockNft);
+
+        // Initialize contract
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Create a circle with 1 week cycle duration
+        let circle_id = client.create_circle(
+            &creator,
+            &1000, // $10 contribution (assuming 6 decimals)
+            &5,
+            &token,
+            &604800, // 1 week in seconds
+            &0,
+            &Some(nft_contract.clone()),
+        );
+
+        // User joins the circle
+        client.join_circle(&user, &circle_id);
+        token_admin_client.mint(&user, &1010);
+
+        // Get initial Group Reserve balance
+        let initial_reserve: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0));
+        assert_eq!(initial_reserve, 0);
+
+        // Simulate time passing beyond deadline (jump forward 2 weeks)
+        env.ledger().set_timestamp(env.ledger().timestamp() + 2 * 604800);
+
+        // A late member owes the 1% penalty on top of the contribution
+        assert_eq!(client.get_outstanding(&circle_id, &user), 1010);
+
+        // Make a late deposit
+        let result = client.try_deposit(&user, &circle_id, &1010);
+        
+        assert!(result.is_ok(), "Late deposit should succeed: {:?}", result);
+        assert_eq!(token::Client::new(&env, &token).balance(&user), 0);
+
+        // Check that Group Reserve received the 1% penalty (10 tokens)
+        let final_reserve: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0));
+        assert_eq!(final_reserve, 10, "Group Reserve should have 10 tokens (1% penalty)");
+
+        // Verify member was marked as having contributed
+        let member_key = DataKey::Member(circle_id, user.clone());
+        let member: Member = env.as_contract(&contract_id, || env.storage().instance().get(&member_key).unwrap());
+        
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert!(circle.contribution_bitmap & (1 << member.index) != 0);
+        assert_eq!(member.contribution_count, 1);
+
+        println!("G�� Late penalty mechanism test passed - 1% penalty correctly routed to Group Reserve");
+    }
+
+    #[test]
+    fn test_on_time_deposit_no_penalty() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let nft_contract = env.register_contract(None, MockNft);
+
+        // Initialize contract
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Create a circle with 1 week cycle duration
+        let circle_id = client.create_circle(
+            &creator,
+            &1000, // $10 contribution
+            &5,
+            &token,
+            &604800, // 1 week in seconds
+            &0,
+            &Some(nft_contract.clone()),
+        );
+
+        // User joins the circle
+        client.join_circle(&user, &circle_id);
+        token_admin_client.mint(&user, &1000);
+
+        // Get initial Group Reserve balance
+        let initial_reserve: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0));
+        assert_eq!(initial_reserve, 0);
+
+        // Make an on-time deposit (don't advance time)
+        let result = client.try_deposit(&user, &circle_id, &1000);
+        
+        assert!(result.is_ok(), "On-time deposit should succeed: {:?}", result);
+
+        // Check that Group Reserve received no penalty
+        let final_reserve: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0));
+        assert_eq!(final_reserve, 0, "Group Reserve should have 0 tokens for on-time deposit");
+
+        println!("G�� On-time deposit test passed - no penalty applied");
+    }
+
+    #[test]
+    fn test_insurance_fund() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let nft_contract = env.register_contract(None, MockNft);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Create circle with 10% insurance fee (1000 bps)
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &5,
+            &token,
+            &604800,
+            &1000, // 10% insurance fee
+            &Some(nft_contract.clone()),
+        );
+
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        token_admin_client.mint(&user1, &1100);
+
+        // User 1 deposits. 1000 + 100 fee. Insurance balance becomes 100.
+        client.deposit(&user1, &circle_id, &1100);
+        
+        let mut circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.insurance_balance, 100);
+
+        // User 1 deposits 9 more times to build up insurance (simulating multiple cycles or members)
+        // In this simplified test, we just force update the balance to test triggering
+        circle.insurance_balance = 1000; 
+        env.as_contract(&contract_id, || env.storage().instance().set(&DataKey::Circle(circle_id), &circle));
+
+        // User 2 defaults. Creator triggers insurance.
+        client.trigger_insurance_coverage(&creator, &circle_id, &user2);
+
+        let circle_after: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        let member2_key = DataKey::Member(circle_id, user2.clone());
+        let member2: Member = env.as_contract(&contract_id, || env.storage().instance().get(&member2_key).unwrap());
+
+        assert!(circle_after.is_insurance_used);
+        assert_eq!(circle_after.insurance_balance, 0);
+        assert!(circle_after.contribution_bitmap & (1 << member2.index) != 0);
+    }
+
+    #[test]
+    fn test_governance_penalty_change() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let nft_contract = env.register_contract(None, MockNft);
 
-        // Check for immediate majority (e.g. 1 member circle)
-        if circle.proposal_votes_bitmap.count_ones() > (circle.member_count as u32 / 2) {
-            circle.late_fee_bps = circle.proposed_late_fee_bps;
-            circle.proposed_late_fee_bps = 0;
-            circle.proposal_votes_bitmap = 0;
-        }
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &5,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
+        );
 
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.join_circle(&user3, &circle_id);
+
+        // Default is 100 bps (1%)
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.late_fee_bps, 100);
+
+        // User 1 proposes 5% (500 bps)
+        client.propose_penalty_change(&user1, &circle_id, &500);
+
+        // User 2 votes
+        client.vote_penalty_change(&user2, &circle_id);
+
+        // Should pass (2 out of 3 votes)
+        let circle_after: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle_after.late_fee_bps, 500);
+        assert_eq!(circle_after.proposed_late_fee_bps, 0);
     }
 
-    fn vote_penalty_change(env: Env, user: Address, circle_id: u64) {
-        user.require_auth();
+    #[test]
+    fn test_nft_membership() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let nft_contract = env.register_contract(None, MockNft);
 
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        
-        // Check if user is a member
-        let member_key = DataKey::Member(user.clone());
-        let member: Member = env.storage().instance().get(&member_key).expect("User is not a member");
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &5,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
+        );
 
-        if !member.is_active {
-            panic!("Member is ejected");
-        }
+        // Join should trigger mint (mocked)
+        client.join_circle(&user, &circle_id);
 
-        if circle.proposed_late_fee_bps == 0 {
-            panic!("No active proposal");
-        }
+        // Verify member is active
+        let member_key = DataKey::Member(circle_id, user.clone());
+        let member: Member = env.as_contract(&contract_id, || env.storage().instance().get(&member_key).unwrap());
+        assert!(member.is_active);
 
-        circle.proposal_votes_bitmap |= 1 << member.index;
+        // Eject member should trigger burn (mocked) and set inactive
+        client.eject_member(&creator, &circle_id, &user);
 
-        if circle.proposal_votes_bitmap.count_ones() > (circle.member_count as u32 / 2) {
-            circle.late_fee_bps = circle.proposed_late_fee_bps;
-            circle.proposed_late_fee_bps = 0;
-            circle.proposal_votes_bitmap = 0;
-        }
+        let member_after: Member = env.as_contract(&contract_id, || env.storage().instance().get(&member_key).unwrap());
+        assert!(!member_after.is_active);
 
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+        // Inactive member cannot deposit
+        assert!(client.try_deposit(&user, &circle_id, &1000).is_err());
     }
 
-    fn eject_member(env: Env, caller: Address, circle_id: u64, member: Address) {
-        caller.require_auth();
-        
-        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        
-        // Only creator can eject
-        if caller != circle.creator {
-            panic!("Unauthorized: Only creator can eject members");
-        }
+    #[test]
+    fn test_dissolve_circle_before_first_payout() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let nft_contract = env.register_contract(None, MockNft);
 
-        let member_key = DataKey::Member(member.clone());
-        let mut member_info: Member = env.storage().instance().get(&member_key).expect("Member not found");
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Zero contribution keeps refunds at 0 so no token transfer is needed
+        let circle_id = client.create_circle(
+            &creator,
+            &0,
+            &5,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
+        );
 
-        if !member_info.is_active {
-            panic!("Member already ejected");
-        }
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
 
-        // Mark as inactive
-        member_info.is_active = false;
-        env.storage().instance().set(&member_key, &member_info);
+        // Only the creator may dissolve unilaterally
+        assert!(client.try_dissolve_circle(&user1, &circle_id).is_err());
 
-        // Burn NFT
-        let token_id = (circle_id as u128) << 64 | (member_info.index as u128);
-        let client = SusuNftClient::new(&env, &circle.nft_contract);
-        client.burn(&member, &token_id);
+        client.dissolve_circle(&creator, &circle_id);
+
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.status, CircleStatus::Dissolved);
+        assert!(!env.as_contract(&contract_id, || env.storage().instance().has(&DataKey::Member(circle_id, user1.clone()))));
+        assert!(!env.as_contract(&contract_id, || env.storage().instance().has(&DataKey::Member(circle_id, user2.clone()))));
+
+        // A dissolved circle accepts no new members
+        let user3 = Address::generate(&env);
+        assert!(client.try_join_circle(&user3, &circle_id).is_err());
     }
-}
 
-// --- FUZZ TESTING MODULES ---
+    #[test]
+    fn test_dissolution_vote_after_payout() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let nft_contract = env.register_contract(None, MockNft);
 
-#[cfg(test)]
-mod fuzz_tests {
-    use super::*;
-    use soroban_sdk::{testutils::{Address as TestAddress, Arbitrary as TestArbitrary}, arbitrary::{Arbitrary, Unstructured}};
-    use std::i128;
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &0,
+            &5,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
+        );
 
-    #[contract]
-    pub struct MockNft;
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.join_circle(&user3, &circle_id);
+
+        // Members of another circle have no say in this one
+        let outsider = Address::generate(&env);
+        let other_id = client.create_circle(&creator, &0, &5, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&outsider, &other_id);
+        assert!(client.try_vote_dissolution(&outsider, &circle_id).is_err());
+        assert!(client.try_propose_penalty_change(&outsider, &circle_id, &500).is_err());
+
+        // Simulate the first payout having happened
+        let mut circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        circle.payout_bitmap = 1;
+        env.as_contract(&contract_id, || env.storage().instance().set(&DataKey::Circle(circle_id), &circle));
+
+        // Creator can no longer dissolve alone
+        assert!(client.try_dissolve_circle(&creator, &circle_id).is_err());
+
+        // 1 of 3 votes is not a majority
+        client.vote_dissolution(&user1, &circle_id);
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.status, CircleStatus::Active);
+
+        // 2 of 3 votes dissolves the circle
+        client.vote_dissolution(&user2, &circle_id);
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.status, CircleStatus::Dissolved);
+    }
 
-    #[contractimpl]
-    impl MockNft {
-        pub fn mint(_env: Env, _to: Address, _id: u128) {}
-        pub fn burn(_env: Env, _from: Address, _id: u128) {}
+    #[test]
+    fn test_init_only_once() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let attacker = Address::generate(&env);
+
+        client.init(&admin);
+
+        assert_eq!(client.get_admin(), admin);
+        assert!(client.has_role(&Role::ProtocolAdmin, &admin));
+        assert!(client.has_role(&Role::Pauser, &admin));
+
+        // A second init cannot overwrite the admin
+        assert!(client.try_init(&attacker).is_err());
+        assert_eq!(client.get_admin(), admin);
     }
 
-    #[derive(Arbitrary, Debug, Clone)]
-    pub struct FuzzTestCase {
-        pub contribution_amount: u64,
-        pub max_members: u16,
-        pub user_id: u64,
+    #[test]
+    fn test_two_step_admin_transfer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let new_admin = Address::generate(&env);
+        let stranger = Address::generate(&env);
+
+        client.init(&admin);
+
+        // Only the admin may nominate
+        assert!(client.try_propose_admin(&stranger, &stranger).is_err());
+
+        client.propose_admin(&admin, &new_admin);
+        assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+
+        // Nomination alone does not move the role
+        assert_eq!(client.get_admin(), admin);
+
+        // Only the nominee may accept
+        assert!(client.try_accept_admin(&stranger).is_err());
+
+        client.accept_admin(&new_admin);
+        assert_eq!(client.get_admin(), new_admin);
+        assert_eq!(client.get_pending_admin(), None);
+        assert!(!client.has_role(&Role::ProtocolAdmin, &admin));
+
+        // The roles the old admin was given at init move over too
+        for role in [Role::FeeManager, Role::Pauser, Role::Upgrader] {
+            assert!(!client.has_role(&role, &admin));
+            assert!(client.has_role(&role, &new_admin));
+        }
+        assert!(client.try_pause_protocol(&admin).is_err());
+
+        // A role the old admin gave away stays with its holder
+        client.grant_role(&new_admin, &Role::Pauser, &stranger);
+        let third_admin = Address::generate(&env);
+        client.propose_admin(&new_admin, &third_admin);
+        client.accept_admin(&third_admin);
+        assert!(client.has_role(&Role::Pauser, &stranger));
+        assert!(client.has_role(&Role::Pauser, &third_admin));
     }
 
     #[test]
-    fn fuzz_test_contribution_amount_edge_cases() {
+    fn test_grant_and_revoke_roles() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let pauser = Address::generate(&env);
+
+        client.init(&admin);
+
+        assert!(!client.has_role(&Role::Pauser, &pauser));
+
+        client.grant_role(&admin, &Role::Pauser, &pauser);
+        assert!(client.has_role(&Role::Pauser, &pauser));
+        assert!(!client.has_role(&Role::Upgrader, &pauser));
+
+        // Role holders cannot grant roles themselves
+        assert!(client.try_grant_role(&pauser, &Role::Upgrader, &pauser).is_err());
+
+        client.revoke_role(&admin, &Role::Pauser, &pauser);
+        assert!(!client.has_role(&Role::Pauser, &pauser));
+    }
+
+    #[test]
+    fn test_protocol_pause_blocks_deposits_but_not_dissolution() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let token = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
         let nft_contract = env.register_contract(None, MockNft);
 
-        // Initialize contract
-        SoroSusuTrait::init(env.clone(), admin.clone());
-
-        // Test case 1: Maximum u64 value (should not panic)
-        let max_circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            u64::MAX,
-            10,
-            token.clone(),
-            604800, // 1 week in seconds
-            0,
-            nft_contract.clone(),
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &0,
+            &5,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
         );
+        client.join_circle(&user, &circle_id);
 
-        let user1 = Address::generate(&env);
-        SoroSusuTrait::join_circle(env.clone(), user1.clone(), max_circle_id);
+        // Only a pauser can trip the breaker
+        assert!(client.try_pause_protocol(&creator).is_err());
 
-        // Mock token balance for the test
-        env.mock_all_auths();
-        
-        // This should not panic even with u64::MAX contribution amount
-        let result = std::panic::catch_unwind(|| {
-            SoroSusuTrait::deposit(env.clone(), user1.clone(), max_circle_id);
-        });
-        
-        // The transfer might fail due to insufficient balance, but it shouldn't panic from overflow
-        assert!(result.is_ok() || result.unwrap_err().downcast::<String>().unwrap().contains("insufficient balance"));
+        client.pause_protocol(&admin);
+        assert!(client.is_paused(&circle_id));
+
+        assert!(client.try_deposit(&user, &circle_id, &0).is_err());
+
+        // A paused week must not make the member late once unpaused
+        env.ledger().set_timestamp(env.ledger().timestamp() + 2 * 604800);
+        client.unpause_protocol(&admin);
+        assert!(!client.is_paused(&circle_id));
+
+        client.deposit(&user, &circle_id, &0);
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.protocol_pause_offset, 2 * 604800);
+
+        // Refund paths stay open while paused
+        client.pause_protocol(&admin);
+        client.dissolve_circle(&creator, &circle_id);
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.status, CircleStatus::Dissolved);
     }
 
     #[test]
-    fn fuzz_test_zero_and_negative_amounts() {
+    fn test_circle_pause_freezes_deadline() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let token = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
         let nft_contract = env.register_contract(None, MockNft);
 
-        // Initialize contract
-        SoroSusuTrait::init(env.clone(), admin.clone());
-
-        // Test case 2: Zero contribution amount (should be allowed but may cause issues)
-        let zero_circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            0,
-            10,
-            token.clone(),
-            604800, // 1 week in seconds
-            0,
-            nft_contract.clone(),
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &5,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
         );
+        client.join_circle(&user, &circle_id);
 
-        let user2 = Address::generate(&env);
-        SoroSusuTrait::join_circle(env.clone(), user2.clone(), zero_circle_id);
+        let deadline_before: u64 = env.as_contract(&contract_id, || env.storage().instance().get::<_, CircleInfo>(&DataKey::Circle(circle_id)).unwrap()).deadline_timestamp;
 
+        // Pauses are capped at one week
+        assert!(client.try_pause_circle(&creator, &circle_id, &(2 * 604800)).is_err());
+
+        client.pause_circle(&creator, &circle_id, &86400);
+        assert!(client.is_paused(&circle_id));
+
+        // Lift the pause after half a day
+        env.ledger().set_timestamp(env.ledger().timestamp() + 43200);
+        client.unpause_circle(&creator, &circle_id);
+
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert!(!client.is_paused(&circle_id));
+        assert_eq!(circle.deadline_timestamp, deadline_before + 43200);
+        assert_eq!(circle.pause_time_used, 43200);
+
+        // Pausing again as each pause ends draws down a four week budget
+        client.pause_circle(&creator, &circle_id, &(604800 - 43200));
+        for _ in 0..3 {
+            env.ledger().set_timestamp(env.ledger().timestamp() + 604800);
+            client.pause_circle(&creator, &circle_id, &604800);
+        }
+        env.ledger().set_timestamp(env.ledger().timestamp() + 604800);
+        assert!(!client.is_paused(&circle_id));
+        assert!(client.try_pause_circle(&creator, &circle_id, &1).is_err());
+    }
+
+    #[test]
+    fn test_migrate_v1_storage() {
+        let env = Env::default();
         env.mock_all_auths();
-        
-        // Zero amount deposit should work (though may not be practically useful)
-        let result = std::panic::catch_unwind(|| {
-            SoroSusuTrait::deposit(env.clone(), user2.clone(), zero_circle_id);
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let nft_contract = env.register_contract(None, MockNft);
+
+        client.init(&admin);
+        assert_eq!(client.storage_version(), STORAGE_VERSION);
+
+        // Nothing to migrate on a fresh deployment
+        assert!(client.try_migrate(&admin, &Vec::new(&env)).is_err());
+
+        // Write data the way a version 1 deployment stored it: two circles, no member lists
+        let user2 = Address::generate(&env);
+        let v1_circle = |id: u64, member_count: u32| CircleInfoV1 {
+            id,
+            creator: creator.clone(),
+            contribution_amount: 1000,
+            max_members: 5,
+            member_count,
+            current_recipient_index: 0,
+            is_active: true,
+            token: token.clone(),
+            deadline_timestamp: 604800,
+            cycle_duration: 604800,
+            contribution_bitmap: 1,
+            payout_bitmap: 0,
+            insurance_balance: 100,
+            insurance_fee_bps: 1000,
+            is_insurance_used: false,
+            late_fee_bps: 100,
+            proposed_late_fee_bps: 0,
+            proposal_votes_bitmap: 0,
+            nft_contract: nft_contract.clone(),
+        };
+        let v1_member = |address: &Address| MemberV1 {
+            address: address.clone(),
+            index: 0,
+            contribution_count: 2,
+            last_contribution_time: 0,
+            is_active: true,
+        };
+        env.as_contract(&contract_id, || {
+            let storage = env.storage().instance();
+            storage.set(&DataKey::Circle(1), &v1_circle(1, 1));
+            storage.set(&DataKey::Circle(2), &v1_circle(2, 1));
+            storage.set(&DataKey::CircleCount, &2u64);
+            storage.set(&DataKeyV1::Member(user.clone()), &v1_member(&user));
+            storage.set(&DataKeyV1::Member(user2.clone()), &v1_member(&user2));
+            storage.set(&DataKeyV1::GroupReserve, &10u64);
+            storage.remove(&DataKey::StorageVersion);
         });
-        
-        assert!(result.is_ok());
+
+        // Only an upgrader may migrate
+        assert!(client.try_migrate(&creator, &vec![&env, user.clone()]).is_err());
+
+        // The supplied member list must match the circle's slots
+        assert!(client.try_migrate(&admin, &Vec::new(&env)).is_err());
+        assert!(client.try_migrate(&admin, &vec![&env, user.clone(), user2.clone()]).is_err());
+
+        // One circle per call; the version only moves once the last one is converted
+        assert!(!client.migrate(&admin, &vec![&env, user.clone()]));
+        assert_eq!(client.storage_version(), 1);
+
+        // An address already converted cannot be claimed by another circle
+        assert!(client.try_migrate(&admin, &vec![&env, user.clone()]).is_err());
+
+        assert!(client.migrate(&admin, &vec![&env, user2.clone()]));
+        assert_eq!(client.storage_version(), STORAGE_VERSION);
+        assert!(client.try_migrate(&admin, &Vec::new(&env)).is_err());
+
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(1)).unwrap());
+        assert_eq!(circle.contribution_amount, 1000i128);
+        assert_eq!(circle.insurance_balance, 100i128);
+        assert_eq!(circle.member_count, 1);
+        assert!(circle.status == CircleStatus::Active);
+        assert_eq!(client.get_members(&2), vec![&env, user2.clone()]);
+
+        let member: Member = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Member(1, user.clone())).unwrap());
+        assert_eq!(member.total_contributed, 2000i128);
+
+        // The shared version 1 reserve counted fees that were never collected
+        assert!(!env.as_contract(&contract_id, || env.storage().instance().has(&DataKeyV1::GroupReserve)));
+
+        // The build version reported to clients is the crate's own
+        assert_eq!(client.version(), String::from_str(&env, env!("CARGO_PKG_VERSION")));
     }
 
     #[test]
-    fn fuzz_test_arbitrary_contribution_amounts() {
+    fn test_token_allowlist_and_limits() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let token = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let unlisted = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
 
-        // Initialize contract
-        SoroSusuTrait::init(env.clone(), admin.clone());
+        client.init(&admin);
 
-        // Test with various edge case amounts
-        let test_amounts = vec![
-            1,                           // Minimum positive amount
-            u32::MAX as u64,            // Large but reasonable amount
-            u64::MAX / 2,               // Very large amount
-            u64::MAX - 1,               // Maximum amount - 1
-            1000000,                    // 1 million
-            0,                          // Zero (already tested above)
-        ];
+        // Min 100, max 10_000 per contribution, at most 50_000 per circle
+        client.list_token(&admin, &token, &100, &10_000, &50_000);
 
-        for (i, amount) in test_amounts.iter().enumerate() {
-            let circle_id = SoroSusuTrait::create_circle(
-                env.clone(),
-                creator.clone(),
-                *amount,
-                10,
-                token.clone(),
-                604800, // 1 week in seconds
-                0,
-                nft_contract.clone(),
-            );
+        let result = client.try_create_circle(&creator, &1000, &5, &unlisted, &604800, &0, &Some(nft_contract.clone()));
+        assert!(result.is_err(), "Unlisted token must be rejected");
 
-            let user = Address::generate(&env);
-            SoroSusuTrait::join_circle(env.clone(), user.clone(), circle_id);
+        let result = client.try_create_circle(&creator, &50, &5, &token, &604800, &0, &Some(nft_contract.clone()));
+        assert!(result.is_err(), "Contribution below token minimum must be rejected");
 
-            env.mock_all_auths();
-            
-            let result = std::panic::catch_unwind(|| {
-                SoroSusuTrait::deposit(env.clone(), user.clone(), circle_id);
-            });
-            
-            // Should not panic due to overflow, only potentially due to insufficient balance
-            match result {
-                Ok(_) => {
-                    // Deposit succeeded
-                    println!("G�� Amount {} succeeded", amount);
-                }
-                Err(e) => {
-                    let error_msg = e.downcast::<String>().unwrap();
-                    // Expected error: insufficient balance, not overflow
-                    assert!(error_msg.contains("insufficient balance") || 
-                           error_msg.contains("underflow") ||
-                           error_msg.contains("overflow"));
-                    println!("G�� Amount {} failed with expected error: {}", amount, error_msg);
-                }
-            }
-        }
+        let result = client.try_create_circle(&creator, &10_000, &10, &token, &604800, &0, &Some(nft_contract.clone()));
+        assert!(result.is_err(), "Circle above the TVL cap must be rejected");
+
+        let circle_id = client.create_circle(&creator, &1000, &5, &token, &604800, &0, &Some(nft_contract.clone()));
+
+        // Delisting blocks new circles but leaves existing ones open
+        client.delist_token(&admin, &token);
+        assert!(!client.get_token_config(&token).unwrap().is_listed);
+
+        assert!(client.try_create_circle(&creator, &1000, &5, &token, &604800, &0, &Some(nft_contract.clone())).is_err());
+
+        let user = Address::generate(&env);
+        client.join_circle(&user, &circle_id);
+    }
+
+    #[test]
+    fn test_yield_strategy_sweep_and_reserve() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user1, &1000);
+        token_admin_client.mint(&user2, &1000);
+        let token_client = token::Client::new(&env, &token);
+        let vault = env.register_contract(None, MockVault);
+        let vault_client = MockVaultClient::new(&env, &vault);
+        vault_client.init(&token);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &2,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
+        );
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+
+        // Unapproved vaults are rejected
+        assert!(client.try_set_yield_strategy(&creator, &circle_id, &vault, &YieldPolicy::Reserve).is_err());
+
+        client.set_vault_approval(&admin, &vault, &true);
+        client.set_yield_strategy(&creator, &circle_id, &vault, &YieldPolicy::Reserve);
+
+        client.deposit(&user1, &circle_id, &1000);
+        client.deposit(&user2, &circle_id, &1000);
+
+        // Simulate an insurance fund built up over earlier rounds
+        let mut circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        circle.insurance_balance = 5000;
+        env.as_contract(&contract_id, || env.storage().instance().set(&DataKey::Circle(circle_id), &circle));
+        token_admin_client.mint(&contract_id, &5000);
+
+        // Everything above one pot (2 x 1000) is invested
+        let swept = client.sweep_to_vault(&circle_id);
+        assert_eq!(swept, 5000);
+        assert_eq!(vault_client.balance(&contract_id.clone()), 5000);
+        assert_eq!(token_client.balance(&vault), 5000);
+        assert_eq!(token_client.balance(&contract_id), 2000);
+
+        token_admin_client.mint(&vault, &500);
+        vault_client.accrue(&contract_id.clone(), &500);
+        assert_eq!(client.get_accrued_yield(&circle_id), 500);
+
+        // Reserve policy routes the harvested yield into the insurance fund
+        client.distribute_payout(&creator, &circle_id);
+
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        let strategy = client.get_yield_strategy(&circle_id).unwrap();
+        assert_eq!(circle.insurance_balance, 5500);
+        assert_eq!(strategy.principal, 5000);
+        assert_eq!(circle.payout_bitmap, 1);
+        assert_eq!(circle.contribution_bitmap, 0);
+        assert_eq!(token_client.balance(&user1), 2000);
+        assert_eq!(token_client.balance(&vault), 5000);
+        assert_eq!(token_client.balance(&contract_id), 500);
+    }
+
+    #[test]
+    fn test_yield_strategy_update_keeps_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&user1, &1000);
+        token_admin_client.mint(&user2, &1000);
+        let vault = env.register_contract(None, MockVault);
+        let other_vault = env.register_contract(None, MockVault);
+        let vault_client = MockVaultClient::new(&env, &vault);
+        vault_client.init(&token);
+        MockVaultClient::new(&env, &other_vault).init(&token);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+        client.set_vault_approval(&admin, &vault, &true);
+        client.set_vault_approval(&admin, &other_vault, &true);
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.deposit(&user1, &circle_id, &1000);
+        client.deposit(&user2, &circle_id, &1000);
+
+        client.set_yield_strategy(&creator, &circle_id, &vault, &YieldPolicy::Distribute);
+        let mut circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        circle.insurance_balance = 3000;
+        env.as_contract(&contract_id, || env.storage().instance().set(&DataKey::Circle(circle_id), &circle));
+        token_admin_client.mint(&contract_id, &3000);
+        assert_eq!(client.sweep_to_vault(&circle_id), 3000);
+
+        // Changing only the policy keeps the circle's shares and principal
+        client.set_yield_strategy(&creator, &circle_id, &vault, &YieldPolicy::Reserve);
+        let strategy = client.get_yield_strategy(&circle_id).unwrap();
+        assert_eq!(strategy.policy, YieldPolicy::Reserve);
+        assert_eq!(strategy.shares, 3000);
+        assert_eq!(strategy.principal, 3000);
+
+        // Another vault has to wait until the position is withdrawn
+        assert!(client.try_set_yield_strategy(&creator, &circle_id, &other_vault, &YieldPolicy::Reserve).is_err());
+
+        // Dissolving brings the whole position home and refunds everyone
+        client.dissolve_circle(&creator, &circle_id);
+        let strategy = client.get_yield_strategy(&circle_id).unwrap();
+        assert_eq!(strategy.shares, 0);
+        assert_eq!(strategy.principal, 0);
+        assert_eq!(token_client.balance(&vault), 0);
+        assert_eq!(token_client.balance(&user1) + token_client.balance(&user2), 5000);
+    }
+
+    #[test]
+    fn test_prepay_applies_credit_each_round() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user1, &2200);
+        token_admin_client.mint(&user2, &1100);
+        token_admin_client.mint(&user3, &1100);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // 10% insurance fee: each round costs 1100
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &3,
+            &token,
+            &604800,
+            &1000,
+            &Some(nft_contract.clone()),
+        );
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.join_circle(&user3, &circle_id);
+
+        // More rounds than the circle has are rejected
+        assert!(client.try_prepay(&user1, &circle_id, &4).is_err());
+
+        // Prepaying 2 rounds covers this round immediately and leaves one in credit
+        client.prepay(&user1, &circle_id, &2);
+        assert_eq!(client.get_credit(&circle_id, &user1), 1100);
+
+        let member1: Member = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Member(circle_id, user1.clone())).unwrap());
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert!(circle.contribution_bitmap & (1 << member1.index) != 0);
+        assert_eq!(circle.insurance_balance, 100);
+
+        client.deposit(&user2, &circle_id, &1100);
+        client.deposit(&user3, &circle_id, &1100);
+        client.distribute_payout(&creator, &circle_id);
+
+        // The next round starts with the remaining credit already applied
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.contribution_bitmap, 1 << member1.index);
+        assert_eq!(client.get_credit(&circle_id, &user1), 0);
+
+        let member1: Member = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Member(circle_id, user1.clone())).unwrap());
+        assert_eq!(member1.contribution_count, 2);
+
+        // A member of another circle cannot prepay into this one
+        let outsider = Address::generate(&env);
+        token_admin_client.mint(&outsider, &1100);
+        let other_id = client.create_circle(&creator, &1000, &3, &token, &604800, &1000, &None);
+        client.join_circle(&outsider, &other_id);
+        assert!(client.try_prepay(&outsider, &circle_id, &1).is_err());
+
+        // Credit that covers a round after its deadline is late, like a deposit would be,
+        // so prepaying then also takes the 1% late fee
+        token_admin_client.mint(&user2, &1111);
+        env.ledger().set_timestamp(10 * 604800);
+        client.prepay(&user2, &circle_id, &1);
+        assert!(client.get_deposit_receipt(&circle_id, &1, &user2).unwrap().late);
+        assert_eq!(client.get_credit(&circle_id, &user2), 0);
+        let penalties: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::RoundPenalties(circle_id, 1)).unwrap_or(0));
+        assert_eq!(penalties, 11);
     }
 
     #[test]
-    fn fuzz_test_boundary_conditions() {
+    fn test_partial_payments_and_late_remainder() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let token = Address::generate(&env);
+        let user = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user, &1105);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // 10% insurance fee: 1100 due per round
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &5,
+            &token,
+            &604800,
+            &1000,
+            &Some(nft_contract.clone()),
+        );
+        client.join_circle(&user, &circle_id);
+        assert_eq!(client.get_outstanding(&circle_id, &user), 1100);
+
+        // A member of another circle cannot pay into this one
+        let outsider = Address::generate(&env);
+        token_admin_client.mint(&outsider, &1100);
+        let other_id = client.create_circle(&creator, &1000, &5, &token, &604800, &1000, &None);
+        client.join_circle(&outsider, &other_id);
+        assert!(client.try_deposit(&outsider, &circle_id, &1100).is_err());
+
+        // First instalment before the deadline does not mark the round paid
+        client.deposit(&user, &circle_id, &600);
+        assert_eq!(client.get_outstanding(&circle_id, &user), 500);
+
+        let member: Member = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Member(circle_id, user.clone())).unwrap());
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.contribution_bitmap & (1 << member.index), 0);
+        assert_eq!(member.contribution_count, 0);
+
+        // The late fee is charged on the 500 remainder only: 1% of 500 = 5, owed on top
+        env.ledger().set_timestamp(env.ledger().timestamp() + 2 * 604800);
+        assert_eq!(client.get_outstanding(&circle_id, &user), 505);
+        client.deposit(&user, &circle_id, &200);
+        assert_eq!(client.get_outstanding(&circle_id, &user), 305);
 
-        // Initialize contract
-        SoroSusuTrait::init(env.clone(), admin.clone());
+        // The fee reaches the reserve once it has been paid
+        let reserve: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0));
+        assert_eq!(reserve, 0);
 
-        // Test boundary conditions for max_members
-        let boundary_tests = vec![
-            (1, "Minimum members"),
-            (64, "Maximum members"),
-            (50, "Typical circle size"),
-        ];
+        // Overpaying only pulls what is owed and completes the round
+        client.deposit(&user, &circle_id, &1000);
+        assert_eq!(client.get_outstanding(&circle_id, &user), 0);
+        assert_eq!(token::Client::new(&env, &token).balance(&user), 0);
 
-        for (max_members, description) in boundary_tests {
-            let circle_id = SoroSusuTrait::create_circle(
-                env.clone(),
-                creator.clone(),
-                1000, // Reasonable contribution amount
-                max_members,
-                token.clone(),
-                604800, // 1 week in seconds
-                0,
-                nft_contract.clone(),
-            );
+        let member: Member = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Member(circle_id, user.clone())).unwrap());
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert!(circle.contribution_bitmap & (1 << member.index) != 0);
+        assert_eq!(circle.insurance_balance, 100);
+        assert_eq!(member.contribution_count, 1);
 
-            // Test joining with maximum allowed members
-            for i in 0..max_members.min(10) { // Limit to 10 for test performance
-                let user = Address::generate(&env);
-                SoroSusuTrait::join_circle(env.clone(), user.clone(), circle_id);
-                
-                env.mock_all_auths();
-                
-                let result = std::panic::catch_unwind(|| {
-                    SoroSusuTrait::deposit(env.clone(), user.clone(), circle_id);
-                });
-                
-                assert!(result.is_ok(), "Deposit failed for {} with max_members {}: {:?}", description, max_members, result);
-            }
-            
-            println!("G�� Boundary test passed: {} (max_members: {})", description, max_members);
-        }
+        // The penalty is not charged twice in the same round
+        let reserve: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0));
+        assert_eq!(reserve, 5);
     }
 
     #[test]
-    fn fuzz_test_concurrent_deposits() {
+    fn test_collect_due_skips_low_allowance() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let token = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        let user4 = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
-
-        // Initialize contract
-        SoroSusuTrait::init(env.clone(), admin.clone());
-
-        let circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            500,
-            5,
-            token.clone(),
-            604800, // 1 week in seconds
-            0,
-            nft_contract.clone(),
+        let token_admin = Address::generate(&env);
+        let asset = env.register_stellar_asset_contract_v2(token_admin.clone());
+        asset.issuer().set_flag(soroban_sdk::testutils::IssuerFlags::RevocableFlag);
+        let token = asset.address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        let contract = contract_id.clone();
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &5,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
         );
-
-        // Create multiple users and test deposits
-        let mut users = Vec::new();
-        for _ in 0..5 {
-            let user = Address::generate(&env);
-            SoroSusuTrait::join_circle(env.clone(), user.clone(), circle_id);
-            users.push(user);
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.join_circle(&user3, &circle_id);
+        client.join_circle(&user4, &circle_id);
+
+        // user1 approved enough, user2 approved too little, user3 did not opt in,
+        // user4 approved enough but their balance has been frozen by the issuer
+        for user in [&user1, &user2, &user3, &user4] {
+            token_admin_client.mint(user, &5000);
         }
+        token_client.approve(&user1, &contract, &5000, &1000);
+        token_client.approve(&user2, &contract, &500, &1000);
+        token_client.approve(&user4, &contract, &5000, &1000);
+        token_admin_client.set_authorized(&user4, &false);
+        client.set_auto_debit(&user1, &circle_id, &true);
+        client.set_auto_debit(&user2, &circle_id, &true);
+        client.set_auto_debit(&user4, &circle_id, &true);
+
+        let report = client.collect_due(&circle_id);
+        assert_eq!(report.collected.len(), 1);
+        assert_eq!(report.collected.get(0).unwrap(), user1);
+        assert_eq!(report.skipped, vec![&env, user2.clone(), user4.clone()]);
+
+        assert_eq!(token_client.balance(&user1), 4000);
+        assert_eq!(token_client.balance(&user2), 5000);
+        assert_eq!(client.get_outstanding(&circle_id, &user1), 0);
+        assert_eq!(client.get_outstanding(&circle_id, &user3), 1000);
+        assert_eq!(client.get_outstanding(&circle_id, &user4), 1000);
+    }
 
+    #[test]
+    fn test_keeper_advance_round_bounty() {
+        let env = Env::default();
         env.mock_all_auths();
-
-        // Test multiple deposits in sequence (simulating concurrent access)
-        for user in users {
-            let result = std::panic::catch_unwind(|| {
-                SoroSusuTrait::deposit(env.clone(), user.clone(), circle_id);
-            });
-            
-            assert!(result.is_ok(), "Concurrent deposit test failed: {:?}", result);
-        }
-        
-        println!("G�� Concurrent deposits test passed");
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        token_admin_client.mint(&admin, &1_000);
+        client.fund_keeper_pool(&admin, &token, &1_000);
+        client.set_keeper_bounty(&admin, &token, &100);
+        let treasury = Address::generate(&env);
+        client.set_protocol_fee(&admin, &50, &treasury);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &10_000,
+            &2,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
+        );
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+
+        // Nothing to advance until everyone has paid
+        assert!(client.try_advance_round(&keeper, &circle_id).is_err());
+
+        token_admin_client.mint(&user1, &10_100);
+        token_admin_client.mint(&user2, &20_000);
+        client.deposit(&user1, &circle_id, &10_000);
+        client.deposit(&user2, &circle_id, &10_000);
+
+        // A round paid out before its due time earns nothing
+        assert_eq!(client.advance_round(&keeper, &circle_id), 0);
+        assert_eq!(token_client.balance(&user1), 20_000);
+        assert_eq!(client.get_keeper_pool(&token).unwrap().balance, 1_000);
+
+        // Once due, the bounty is capped at 0.5% of the 20_000 pot
+        client.deposit(&user1, &circle_id, &10_000);
+        client.deposit(&user2, &circle_id, &10_000);
+        env.ledger().set_timestamp(2 * 604800);
+        let bounty = client.advance_round(&keeper, &circle_id);
+        assert_eq!(bounty, 100);
+        assert_eq!(token_client.balance(&keeper), 100);
+        assert_eq!(client.get_keeper_pool(&token).unwrap().balance, 900);
+
+        // A circle cycled faster than a day earns nothing, however overdue
+        let fast_id = client.create_circle(&creator, &10_000, &1, &token, &60, &0, &None);
+        client.join_circle(&user1, &fast_id);
+        client.deposit(&user1, &fast_id, &10_000);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 120);
+        assert_eq!(client.advance_round(&keeper, &fast_id), 0);
+        assert_eq!(token_client.balance(&keeper), 100);
+
+        // A circle's bounties never exceed the protocol fees it has paid, so a keeper
+        // farming its own daily circles gets back no more than it paid in fees
+        let farm_id = client.create_circle(&keeper, &10_000, &1, &token, &86400, &0, &None);
+        token_admin_client.mint(&keeper, &10_000);
+        client.join_circle(&keeper, &farm_id);
+        client.deposit(&keeper, &farm_id, &10_000);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+        assert_eq!(client.advance_round(&keeper, &farm_id), 50);
+        assert_eq!(token_client.balance(&keeper), 10_100);
+
+        // Without a protocol fee there is nothing to pay a bounty from
+        client.set_protocol_fee(&admin, &0, &treasury);
+        let free_id = client.create_circle(&keeper, &10_000, &1, &token, &86400, &0, &None);
+        client.join_circle(&keeper, &free_id);
+        client.deposit(&keeper, &free_id, &10_000);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+        assert_eq!(client.advance_round(&keeper, &free_id), 0);
+        assert_eq!(client.get_keeper_pool(&token).unwrap().balance, 850);
     }
 
     #[test]
-    fn test_late_penalty_mechanism() {
+    fn test_keeper_settle_round_covers_default() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let user = Address::generate(&env);
-        let token = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
-
-        // Initialize contract
-        SoroSusuTrait::init(env.clone(), admin.clone());
-
-        // Create a circle with 1 week cycle duration
-        let circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            1000, // $10 contribution (assuming 6 decimals)
-            5,
-            token.clone(),
-            604800, // 1 week in seconds
-            0,
-            nft_contract.clone(),
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user1, &1100);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &2,
+            &token,
+            &604800,
+            &1000,
+            &Some(nft_contract.clone()),
         );
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.deposit(&user1, &circle_id, &1100);
 
-        // User joins the circle
-        SoroSusuTrait::join_circle(env.clone(), user.clone(), circle_id);
-
-        // Mock token balance for the test
-        env.mock_all_auths();
+        let mut circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        circle.insurance_balance = 1000;
+        env.as_contract(&contract_id, || env.storage().instance().set(&DataKey::Circle(circle_id), &circle));
 
-        // Get initial Group Reserve balance
-        let initial_reserve: u64 = env.storage().instance().get(&DataKey::GroupReserve).unwrap_or(0);
-        assert_eq!(initial_reserve, 0);
+        // Too early to declare a default
+        assert!(client.try_settle_round(&keeper, &circle_id).is_err());
 
-        // Simulate time passing beyond deadline (jump forward 2 weeks)
         env.ledger().set_timestamp(env.ledger().timestamp() + 2 * 604800);
 
-        // Make a late deposit
-        let result = std::panic::catch_unwind(|| {
-            SoroSusuTrait::deposit(env.clone(), user.clone(), circle_id);
-        });
-        
-        assert!(result.is_ok(), "Late deposit should succeed: {:?}", result);
-
-        // Check that Group Reserve received the 1% penalty (10 tokens)
-        let final_reserve: u64 = env.storage().instance().get(&DataKey::GroupReserve).unwrap_or(0);
-        assert_eq!(final_reserve, 10, "Group Reserve should have 10 tokens (1% penalty)");
+        // No keeper pool for this token, so the settlement earns nothing
+        let bounty = client.settle_round(&keeper, &circle_id);
+        assert_eq!(bounty, 0);
 
-        // Verify member was marked as having contributed
-        let member_key = DataKey::Member(user.clone());
-        let member: Member = env.storage().instance().get(&member_key).unwrap();
-        
-        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        assert!(circle.contribution_bitmap & (1 << member.index) != 0);
-        assert_eq!(member.contribution_count, 1);
+        let member2: Member = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Member(circle_id, user2.clone())).unwrap());
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert!(circle.contribution_bitmap & (1 << member2.index) != 0);
+        assert!(circle.is_insurance_used);
+        assert_eq!(member2.insurance_debt, 1000);
 
-        println!("G�� Late penalty mechanism test passed - 1% penalty correctly routed to Group Reserve");
+        // The same round cannot be settled twice
+        assert!(client.try_settle_round(&keeper, &circle_id).is_err());
     }
 
     #[test]
-    fn test_on_time_deposit_no_penalty() {
+    fn test_fixed_schedule_and_grace_period() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let user = Address::generate(&env);
-        let token = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
-
-        // Initialize contract
-        SoroSusuTrait::init(env.clone(), admin.clone());
-
-        // Create a circle with 1 week cycle duration
-        let circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            1000, // $10 contribution
-            5,
-            token.clone(),
-            604800, // 1 week in seconds
-            0,
-            nft_contract.clone(),
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user1, &1000);
+        token_admin_client.mint(&user2, &1000);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(
+            &creator,
+            &1000,
+            &3,
+            &token,
+            &604800,
+            &0,
+            &Some(nft_contract.clone()),
         );
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.set_grace_period(&creator, &circle_id, &86400);
+
+        let start = env.ledger().timestamp();
+        let schedule = client.get_schedule(&circle_id);
+        assert_eq!(schedule.len(), 3);
+        assert_eq!(schedule.get(2).unwrap().due_time, start + 3 * 604800);
+        assert_eq!(schedule.get(0).unwrap().recipient, Some(user1.clone()));
+        assert_eq!(schedule.get(2).unwrap().recipient, None);
+
+        // Inside the grace window the payment still counts as on time
+        env.ledger().set_timestamp(start + 604800 + 3600);
+        client.deposit(&user1, &circle_id, &1000);
+        assert!(client.was_paid_on_time(&circle_id, &0, &user1));
+        let reserve: i128 = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::GroupReserve(circle_id)).unwrap_or(0));
+        assert_eq!(reserve, 0);
+
+        // Paying does not push the due time back
+        let circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        assert_eq!(circle.deadline_timestamp, start + 604800);
+
+        env.ledger().set_timestamp(start + 604800 + 2 * 86400);
+        client.deposit(&user2, &circle_id, &1000);
+        assert!(!client.was_paid_on_time(&circle_id, &0, &user2));
+
+        // Grace cannot swallow a whole cycle
+        assert!(client.try_set_grace_period(&creator, &circle_id, &604800).is_err());
+
+        // A slot whose member record is missing keeps its round without a recipient
+        env.as_contract(&contract_id, || env.storage().instance().remove(&DataKey::Member(circle_id, user2.clone())));
+        let schedule = client.get_schedule(&circle_id);
+        assert_eq!(schedule.len(), 3);
+        assert!(schedule.iter().all(|round| round.recipient != Some(user2.clone())));
+    }
 
-        // User joins the circle
-        SoroSusuTrait::join_circle(env.clone(), user.clone(), circle_id);
-
-        // Mock token balance for the test
+    #[test]
+    fn test_read_views_and_list_circles() {
+        let env = Env::default();
         env.mock_all_auths();
-
-        // Get initial Group Reserve balance
-        let initial_reserve: u64 = env.storage().instance().get(&DataKey::GroupReserve).unwrap_or(0);
-        assert_eq!(initial_reserve, 0);
-
-        // Make an on-time deposit (don't advance time)
-        let result = std::panic::catch_unwind(|| {
-            SoroSusuTrait::deposit(env.clone(), user.clone(), circle_id);
-        });
-        
-        assert!(result.is_ok(), "On-time deposit should succeed: {:?}", result);
-
-        // Check that Group Reserve received no penalty
-        let final_reserve: u64 = env.storage().instance().get(&DataKey::GroupReserve).unwrap_or(0);
-        assert_eq!(final_reserve, 0, "Group Reserve should have 0 tokens for on-time deposit");
-
-        println!("G�� On-time deposit test passed - no penalty applied");
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        let other_token = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user1, &1000);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+        client.list_token(&admin, &other_token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(&creator, &1000, &3, &token, &604800, &0, &Some(nft_contract.clone()));
+        let other_id = client.create_circle(&creator, &1000, &3, &other_token, &604800, &0, &Some(nft_contract.clone()));
+        let dissolved_id = client.create_circle(&creator, &1000, &3, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.dissolve_circle(&creator, &dissolved_id);
+
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.deposit(&user1, &circle_id, &1000);
+
+        assert_eq!(client.get_circle(&circle_id).member_count, 2);
+        assert_eq!(client.get_member(&circle_id, &user1).contribution_count, 1);
+        assert_eq!(client.get_members(&circle_id), vec![&env, user1.clone(), user2.clone()]);
+
+        let status = client.get_contribution_status(&circle_id);
+        assert_eq!(status.get(user1.clone()), Some(true));
+        assert_eq!(status.get(user2.clone()), Some(false));
+
+        // Membership is checked against the requested circle
+        assert!(client.try_get_member(&other_id, &user1).is_err());
+        assert!(client.try_get_member(&circle_id, &outsider).is_err());
+
+        // Filters and paging
+        let (all, next) = client.list_circles(&0, &10, &None, &None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(next, 0);
+        let (active, _) = client.list_circles(&0, &10, &Some(CircleStatus::Active), &Some(token.clone()));
+        assert_eq!(active.len(), 1);
+        assert_eq!(active.get(0).unwrap().id, circle_id);
+        let (page, next) = client.list_circles(&other_id, &1, &None, &None);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap().id, other_id);
+        assert_eq!(next, dissolved_id);
+
+        // A filter that matches nothing still returns a cursor after one page of IDs
+        let (none, next) = client.list_circles(&0, &2, &Some(CircleStatus::Completed), &None);
+        assert_eq!(none.len(), 0);
+        assert_eq!(next, dissolved_id);
+        let (none, next) = client.list_circles(&next, &2, &Some(CircleStatus::Completed), &None);
+        assert_eq!(none.len(), 0);
+        assert_eq!(next, 0);
     }
 
     #[test]
-    fn test_insurance_fund() {
+    fn test_nft_metadata_and_completion_badge() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
         let user1 = Address::generate(&env);
         let user2 = Address::generate(&env);
-        let token = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
+        let nft_client = MockNftClient::new(&env, &nft_contract);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user1, &2200);
+        token_admin_client.mint(&user2, &2200);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &1000, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+
+        let token_id = encode_token_id(circle_id, 1);
+        assert_eq!(decode_token_id(token_id), (circle_id, 1));
+        let metadata = nft_client.metadata(&token_id).unwrap();
+        assert_eq!(metadata.slot, 1);
+        assert_eq!(metadata.token, token);
+        assert_eq!(metadata.contribution_amount, 1000);
+
+        // Round 1: user2 defaults and the insurance fund covers them
+        client.deposit(&user1, &circle_id, &1100);
+        let mut circle: CircleInfo = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap());
+        circle.insurance_balance = 1000;
+        env.as_contract(&contract_id, || env.storage().instance().set(&DataKey::Circle(circle_id), &circle));
+        token_admin_client.mint(&contract_id.clone(), &1000);
+        client.trigger_insurance_coverage(&creator, &circle_id, &user2);
+        client.distribute_payout(&creator, &circle_id);
+        assert!(!nft_client.has_badge(&encode_token_id(circle_id, 0)));
+
+        // Round 2 completes the circle
+        client.deposit(&user1, &circle_id, &1100);
+        client.deposit(&user2, &circle_id, &1100);
+        client.distribute_payout(&creator, &circle_id);
+
+        assert!(nft_client.has_badge(&encode_token_id(circle_id, 0)));
+        assert!(!nft_client.has_badge(&encode_token_id(circle_id, 1)));
+    }
 
-        SoroSusuTrait::init(env.clone(), admin.clone());
+    #[test]
+    fn test_optional_nft_and_pending_retry() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let nft_contract = env.register_contract(None, MockNft);
+        let nft_client = MockNftClient::new(&env, &nft_contract);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Circles can run without an NFT at all
+        let plain_id = client.create_circle(&creator, &1000, &3, &token, &604800, &0, &None);
+        client.join_circle(&user1, &plain_id);
+        client.eject_member(&creator, &plain_id, &user1);
+        assert!(client.get_pending_nft(&plain_id, &0).is_empty());
+
+        // An NFT outage does not block joining; the mint waits for a retry
+        let circle_id = client.create_circle(&creator, &1000, &3, &token, &604800, &0, &Some(nft_contract.clone()));
+        nft_client.set_broken(&true);
+        client.join_circle(&user2, &circle_id);
+        client.join_circle(&user3, &circle_id);
+        assert_eq!(client.get_pending_nft(&circle_id, &0).len(), 1);
+        assert!(!client.retry_nft(&circle_id, &0));
+
+        // Ejecting before the mint landed just drops it
+        client.eject_member(&creator, &circle_id, &user3);
+        assert!(client.get_pending_nft(&circle_id, &1).is_empty());
+
+        nft_client.set_broken(&false);
+        assert!(client.retry_nft(&circle_id, &0));
+        assert!(client.get_pending_nft(&circle_id, &0).is_empty());
+        assert_eq!(nft_client.metadata(&encode_token_id(circle_id, 0)).unwrap().slot, 0);
+        assert!(nft_client.metadata(&encode_token_id(circle_id, 1)).is_none());
+    }
 
-        // Create circle with 10% insurance fee (1000 bps)
-        let circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            1000,
-            5,
-            token.clone(),
-            604800,
-            1000, // 10% insurance fee
-            nft_contract.clone(),
-        );
+    #[test]
+    fn test_protocol_fee_taken_from_payout() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&user1, &1000);
+        token_admin_client.mint(&user2, &1000);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // Over 100% is rejected
+        assert!(client.try_set_protocol_fee(&admin, &10001, &treasury).is_err());
+        client.set_protocol_fee(&admin, &50, &treasury);
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.deposit(&user1, &circle_id, &1000);
+        client.deposit(&user2, &circle_id, &1000);
+        client.distribute_payout(&creator, &circle_id);
+
+        // 0.5% of the 2000 pot goes to the treasury
+        assert_eq!(token_client.balance(&treasury), math::protocol_fee(2000, 50));
+        assert_eq!(token_client.balance(&user1), 1990);
+    }
 
-        SoroSusuTrait::join_circle(env.clone(), user1.clone(), circle_id);
-        SoroSusuTrait::join_circle(env.clone(), user2.clone(), circle_id);
+    #[test]
+    fn test_one_deposit_per_round_with_receipts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&user1, &5000);
+        token_admin_client.mint(&user2, &5000);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &1000, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+
+        env.ledger().set_timestamp(1000);
+        client.deposit(&user1, &circle_id, &1100);
+        let receipt = client.get_deposit_receipt(&circle_id, &0, &user1).unwrap();
+        assert_eq!(receipt, DepositReceipt { amount: 1100, paid_at: 1000, late: false });
+
+        // Receipts are persistent entries of their own with a long TTL
+        let receipt_key = DataKey::Deposit(circle_id, 0, user1.clone());
+        let ttl = env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&receipt_key));
+        assert_eq!(ttl, HISTORY_TTL);
+
+        // A second deposit in the same round is rejected and pulls nothing
+        assert!(client.try_deposit(&user1, &circle_id, &1100).is_err());
+        assert_eq!(token_client.balance(&user1), 3900);
+        let member1: Member = env.as_contract(&contract_id, || env.storage().instance().get(&DataKey::Member(circle_id, user1.clone())).unwrap());
+        assert_eq!(member1.contribution_count, 1);
+
+        // Paying ahead is explicit: prepay credits the next round instead
+        client.prepay(&user1, &circle_id, &1);
+        assert_eq!(client.get_credit(&circle_id, &user1), 1100);
+
+        // A late payer gets a receipt marked late, after paying the 1% late fee too
+        env.ledger().set_timestamp(2 * 604800);
+        client.deposit(&user2, &circle_id, &1111);
+        assert!(client.get_deposit_receipt(&circle_id, &0, &user2).unwrap().late);
+
+        // Credit pays round 1 as it opens, with its own receipt
+        client.distribute_payout(&creator, &circle_id);
+        let receipt = client.get_deposit_receipt(&circle_id, &1, &user1).unwrap();
+        assert!(!receipt.late);
+        assert!(client.get_deposit_receipt(&circle_id, &1, &user2).is_none());
+    }
 
+    #[test]
+    fn test_round_record_written_on_payout() {
+        let env = Env::default();
         env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        token_admin_client.mint(&user1, &1000);
+        token_admin_client.mint(&user2, &1010);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        let due_time = client.get_circle(&circle_id).deadline_timestamp;
+
+        client.deposit(&user1, &circle_id, &1000);
+        assert_eq!(client.get_round_record(&circle_id, &0), None);
+
+        // user2 pays after the deadline and is charged the 1% late fee
+        env.ledger().set_timestamp(due_time + 10);
+        client.deposit(&user2, &circle_id, &1010);
+        client.distribute_payout(&creator, &circle_id);
+
+        let record = client.get_round_record(&circle_id, &0).unwrap();
+        assert_eq!(record.due_time, due_time);
+        assert_eq!(record.paid_out_at, due_time + 10);
+        assert_eq!(record.contributors, 0b11);
+        assert_eq!(record.late_payers, 0b10);
+        assert_eq!(record.insurance_covered, 0);
+        assert_eq!(record.guarantee_covered, 0);
+        assert_eq!(record.recipient, user1);
+        assert_eq!(record.gross_payout, 2000);
+        assert_eq!(record.net_payout, 2000);
+        assert_eq!(record.protocol_fee, 0);
+        assert_eq!(record.penalties, 10);
+
+        // Records are persistent entries of their own with a long TTL
+        let record_key = DataKey::RoundRecord(circle_id, 0);
+        let ttl = env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&record_key));
+        assert_eq!(ttl, HISTORY_TTL);
+
+        let records = client.list_round_records(&circle_id, &0, &10);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records.get(0).unwrap(), record);
+        assert_eq!(client.list_round_records(&circle_id, &1, &10).len(), 0);
+    }
 
-        // User 1 deposits. 1000 + 100 fee. Insurance balance becomes 100.
-        SoroSusuTrait::deposit(env.clone(), user1.clone(), circle_id);
-        
-        let mut circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        assert_eq!(circle.insurance_balance, 100);
+    #[test]
+    fn test_creator_bond_returned_or_slashed() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let users: std::vec::Vec<Address> = (0..6).map(|_| Address::generate(&env)).collect();
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&creator, &600);
+        for user in users.iter() {
+            token_admin_client.mint(user, &2000);
+        }
 
-        // User 1 deposits 9 more times to build up insurance (simulating multiple cycles or members)
-        // In this simplified test, we just force update the balance to test triggering
-        circle.insurance_balance = 1000; 
-        env.storage().instance().set(&DataKey::Circle(circle_id), &circle);
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+        // 10% of max TVL: 200 for a 2 x 1000 circle
+        client.set_creator_bond(&admin, &1000);
+
+        let completed_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        let voted_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        let abandoned_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        assert_eq!(token_client.balance(&creator), 0);
+        assert_eq!(client.get_creator_bond(&completed_id).unwrap().amount, 200);
+
+        // A completed circle hands the bond back
+        client.join_circle(&users[0], &completed_id);
+        client.join_circle(&users[1], &completed_id);
+        for _ in 0..2 {
+            client.deposit(&users[0], &completed_id, &1000);
+            client.deposit(&users[1], &completed_id, &1000);
+            client.distribute_payout(&creator, &completed_id);
+        }
+        assert_eq!(client.get_creator_bond(&completed_id).unwrap().status, BondStatus::Returned);
+        assert_eq!(token_client.balance(&creator), 200);
+
+        // A majority vote removes the creator and shares out the bond
+        client.join_circle(&users[2], &voted_id);
+        client.join_circle(&users[3], &voted_id);
+        assert!(client.try_vote_remove_creator(&users[0], &voted_id).is_err());
+        client.vote_remove_creator(&users[2], &voted_id);
+        assert_eq!(client.get_creator_bond(&voted_id).unwrap().status, BondStatus::Locked);
+        client.vote_remove_creator(&users[3], &voted_id);
+        assert_eq!(client.get_creator_bond(&voted_id).unwrap().status, BondStatus::Slashed);
+        assert_eq!(token_client.balance(&users[2]), 2100);
+        assert_eq!(token_client.balance(&users[3]), 2100);
+
+        // A removed creator keeps none of their powers
+        assert!(client.try_eject_member(&creator, &voted_id, &users[2]).is_err());
+
+        // Members can fill the removed creator's seat once, and only that seat
+        assert!(client.try_vote_replace_organiser(&users[2], &voted_id, &users[3], &users[2]).is_err());
+        client.vote_replace_organiser(&users[2], &voted_id, &creator, &users[2]);
+        client.vote_replace_organiser(&users[3], &voted_id, &creator, &users[2]);
+        assert_eq!(client.get_organisers(&voted_id).members, vec![&env, users[2].clone()]);
+        assert!(client.try_vote_replace_organiser(&users[2], &voted_id, &creator, &users[3]).is_err());
+
+        // Members who stop paying do not make the creator liable, however long it lasts
+        client.join_circle(&users[4], &abandoned_id);
+        client.join_circle(&users[5], &abandoned_id);
+        let deadline = client.get_circle(&abandoned_id).deadline_timestamp;
+        env.ledger().set_timestamp(deadline + 10 * 604800);
+        assert!(client.try_report_abandonment(&abandoned_id).is_err());
+
+        // A funded round left unpaid for two cycles can be reported by anyone
+        client.deposit(&users[4], &abandoned_id, &1010);
+        client.deposit(&users[5], &abandoned_id, &1010);
+        assert!(client.try_report_abandonment(&abandoned_id).is_err());
+        env.ledger().set_timestamp(deadline + 12 * 604800);
+        assert!(client.try_report_abandonment(&abandoned_id).is_err());
+        env.ledger().set_timestamp(deadline + 12 * 604800 + 1);
+        client.report_abandonment(&abandoned_id);
+        assert_eq!(token_client.balance(&users[4]), 1090);
+        assert_eq!(token_client.balance(&creator), 200);
+    }
 
-        // User 2 defaults. Creator triggers insurance.
-        SoroSusuTrait::trigger_insurance_coverage(env.clone(), creator.clone(), circle_id, user2.clone());
+    #[test]
+    fn test_organiser_threshold_and_creator_handover() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let organiser1 = Address::generate(&env);
+        let organiser2 = Address::generate(&env);
+        let successor = Address::generate(&env);
+        let stand_in = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&creator, &300);
+        token_admin_client.mint(&successor, &300);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+        client.set_creator_bond(&admin, &1000);
+
+        let circle_id = client.create_circle(&creator, &1000, &3, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.join_circle(&user3, &circle_id);
+
+        // 2-of-3 organisers; the creator alone can no longer act
+        let organisers = vec![&env, creator.clone(), organiser1.clone(), organiser2.clone()];
+        client.set_organisers(&creator, &circle_id, &organisers, &2);
+        assert_eq!(client.get_organisers(&circle_id).threshold, 2);
+
+        client.eject_member(&creator, &circle_id, &user3);
+        assert!(client.get_member(&circle_id, &user3).is_active);
+        client.eject_member(&organiser1, &circle_id, &user3);
+        assert!(!client.get_member(&circle_id, &user3).is_active);
+
+        assert!(client.try_eject_member(&user1, &circle_id, &user2).is_err());
+
+        // Two-step handover: the successor takes the seat and posts the bond
+        client.propose_creator(&creator, &circle_id, &successor);
+        assert!(client.try_accept_creator(&organiser1, &circle_id).is_err());
+        client.accept_creator(&successor, &circle_id);
+
+        assert_eq!(client.get_circle(&circle_id).creator, successor);
+        assert_eq!(client.get_creator_bond(&circle_id).unwrap().creator, successor);
+        assert_eq!(token_client.balance(&creator), 300);
+        assert_eq!(token_client.balance(&successor), 0);
+        assert_eq!(client.get_organisers(&circle_id).members, vec![&env, successor.clone(), organiser1.clone(), organiser2.clone()]);
+        assert_eq!(client.get_pending_creator(&circle_id), None);
+
+        // A one-person circle elsewhere gives no vote here
+        let sybil = Address::generate(&env);
+        token_admin_client.mint(&sybil, &300);
+        let sybil_id = client.create_circle(&sybil, &1000, &1, &token, &604800, &0, &None);
+        client.join_circle(&sybil, &sybil_id);
+        assert!(client.try_vote_replace_organiser(&sybil, &circle_id, &organiser2, &sybil).is_err());
+
+        // Naming someone who is not an organiser cannot add a seat
+        assert!(client.try_vote_replace_organiser(&user1, &circle_id, &user2, &stand_in).is_err());
+
+        // A majority of members swap out an unresponsive organiser
+        client.vote_replace_organiser(&user1, &circle_id, &organiser2, &stand_in);
+        assert!(client.get_organisers(&circle_id).members.contains(&organiser2));
+        client.vote_replace_organiser(&user2, &circle_id, &organiser2, &stand_in);
+        let organisers = client.get_organisers(&circle_id);
+        assert_eq!(organisers.members, vec![&env, successor.clone(), organiser1.clone(), stand_in.clone()]);
+        assert_eq!(organisers.epoch, 3);
+    }
 
-        let circle_after: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        let member2_key = DataKey::Member(user2.clone());
-        let member2: Member = env.storage().instance().get(&member2_key).unwrap();
+    #[test]
+    fn test_sponsored_and_delegated_deposits() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let relative = Address::generate(&env);
+        let delegate = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&relative, &1000);
+        token_admin_client.mint(&user2, &2000);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+
+        // A relative abroad pays user1's round; user1 is credited
+        client.deposit_for(&relative, &user1, &circle_id);
+        assert_eq!(token_client.balance(&relative), 0);
+        assert!(client.get_deposit_receipt(&circle_id, &0, &user1).is_some());
+        assert_eq!(client.get_member(&circle_id, &user1).total_contributed, 1000);
+
+        client.deposit(&user2, &circle_id, &1000);
+        client.distribute_payout(&creator, &circle_id);
+
+        // A delegate draws on user2's allowance, but only up to the delegation limit
+        token_client.approve(&user2, &contract_id.clone(), &1000, &1000);
+        client.set_delegate(&user2, &circle_id, &delegate, &500, &true, &0);
+        assert!(client.try_delegate_deposit(&delegate, &user2, &circle_id).is_err());
+
+        client.set_delegate(&user2, &circle_id, &delegate, &1000, &true, &0);
+        client.delegate_deposit(&delegate, &user2, &circle_id);
+        assert_eq!(token_client.balance(&user2), 0);
+        assert_eq!(client.get_delegate(&circle_id, &user2, &delegate).unwrap().deposit_allowance, 0);
+
+        // Delegates can vote for the member until revoked
+        client.delegate_vote(&delegate, &user2, &circle_id, &MemberVote::Dissolution);
+        assert_eq!(client.get_circle(&circle_id).dissolution_votes_bitmap, 0b10);
+
+        client.revoke_delegate(&user2, &circle_id, &delegate);
+        assert!(client.try_delegate_vote(&delegate, &user2, &circle_id, &MemberVote::Dissolution).is_err());
+
+        // Delegations lapse at their expiry
+        client.set_delegate(&user1, &circle_id, &delegate, &0, &true, &100);
+        env.ledger().set_timestamp(101);
+        assert!(client.try_delegate_vote(&delegate, &user1, &circle_id, &MemberVote::Dissolution).is_err());
+    }
 
-        assert!(circle_after.is_insurance_used);
-        assert_eq!(circle_after.insurance_balance, 0);
-        assert!(circle_after.contribution_bitmap & (1 << member2.index) != 0);
+    #[test]
+    fn test_unreachable_recipient_payout_held_for_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let relative = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        // A classic account with no trustline for the token: it can never be paid directly
+        let user1 = Address::from_string(&String::from_str(&env, "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"));
+        let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&relative, &2000);
+        token_admin_client.mint(&user2, &2000);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+        client.set_protocol_fee(&admin, &0, &treasury);
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+
+        // The round still pays out; user1's pot is held for them
+        client.deposit_for(&relative, &user1, &circle_id);
+        client.deposit(&user2, &circle_id, &1000);
+        client.distribute_payout(&creator, &circle_id);
+        let held = client.get_claimable(&circle_id, &user1).unwrap();
+        assert_eq!(held.amount, 2000);
+
+        // user2 opts out of pushes and collects their payout themselves
+        client.set_pull_payouts(&user2, &circle_id, &true);
+        client.deposit_for(&relative, &user1, &circle_id);
+        client.deposit(&user2, &circle_id, &1000);
+        client.distribute_payout(&creator, &circle_id);
+        assert_eq!(token_client.balance(&user2), 0);
+        assert_eq!(client.claim(&user2, &circle_id), 2000);
+        assert_eq!(token_client.balance(&user2), 2000);
+        assert_eq!(client.get_claimable(&circle_id, &user2), None);
+
+        // Left unclaimed for five years, the admin can send user1's payout to the treasury
+        env.ledger().set_timestamp(held.credited_at + 157680000);
+        assert!(client.try_recover_unclaimed(&admin, &circle_id, &user1).is_err());
+        env.ledger().set_timestamp(held.credited_at + 157680001);
+        assert!(client.try_recover_unclaimed(&user2, &circle_id, &user1).is_err());
+        assert_eq!(client.recover_unclaimed(&admin, &circle_id, &user1), 2000);
+        assert_eq!(token_client.balance(&treasury), 2000);
     }
 
     #[test]
-    fn test_governance_penalty_change() {
+    fn test_payout_split_and_lock() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
         let user1 = Address::generate(&env);
         let user2 = Address::generate(&env);
         let user3 = Address::generate(&env);
-        let token = Address::generate(&env);
+        let savings = Address::generate(&env);
+        let supplier = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        for user in [&user1, &user2, &user3] {
+            token_admin_client.mint(user, &2000);
+        }
 
-        SoroSusuTrait::init(env.clone(), admin.clone());
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
 
-        let circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            1000,
-            5,
-            token.clone(),
-            604800,
-            0,
-            nft_contract.clone(),
-        );
+        let circle_id = client.create_circle(&creator, &1000, &3, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&user1, &circle_id);
+        client.join_circle(&user2, &circle_id);
+        client.join_circle(&user3, &circle_id);
 
-        SoroSusuTrait::join_circle(env.clone(), user1.clone(), circle_id);
-        SoroSusuTrait::join_circle(env.clone(), user2.clone(), circle_id);
-        SoroSusuTrait::join_circle(env.clone(), user3.clone(), circle_id);
+        // Shares must add up to exactly 100%
+        let bad = vec![&env, SplitShare { to: savings.clone(), bps: 7000 }, SplitShare { to: supplier.clone(), bps: 2000 }];
+        assert!(client.try_set_payout_split(&user2, &circle_id, &bad).is_err());
 
-        env.mock_all_auths();
+        let split = vec![&env, SplitShare { to: savings.clone(), bps: 7000 }, SplitShare { to: supplier.clone(), bps: 3000 }];
+        client.set_payout_split(&user2, &circle_id, &split);
+        assert_eq!(client.get_payout_split(&circle_id, &user2), split);
 
-        // Default is 100 bps (1%)
-        let circle: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        assert_eq!(circle.late_fee_bps, 100);
+        // user1 is paid in the round already running, so their split is locked
+        assert!(client.try_set_payout_split(&user1, &circle_id, &split).is_err());
 
-        // User 1 proposes 5% (500 bps)
-        SoroSusuTrait::propose_penalty_change(env.clone(), user1.clone(), circle_id, 500);
+        for _ in 0..2 {
+            for user in [&user1, &user2, &user3] {
+                client.deposit(user, &circle_id, &1000);
+            }
+            client.distribute_payout(&creator, &circle_id);
 
-        // User 2 votes
-        SoroSusuTrait::vote_penalty_change(env.clone(), user2.clone(), circle_id);
+            // Once user2's round is under way, the split can no longer change
+            assert!(client.try_set_payout_split(&user2, &circle_id, &Vec::new(&env)).is_err());
+        }
 
-        // Should pass (2 out of 3 votes)
-        let circle_after: CircleInfo = env.storage().instance().get(&DataKey::Circle(circle_id)).unwrap();
-        assert_eq!(circle_after.late_fee_bps, 500);
-        assert_eq!(circle_after.proposed_late_fee_bps, 0);
+        assert_eq!(token_client.balance(&savings), 2100);
+        assert_eq!(token_client.balance(&supplier), 900);
+        assert_eq!(token_client.balance(&user2), 0);
+        assert_eq!(client.get_member(&circle_id, &user2).total_received, 3000);
     }
 
     #[test]
-    fn test_nft_membership() {
+    fn test_guarantor_backs_newcomer_default() {
         let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         let creator = Address::generate(&env);
-        let user = Address::generate(&env);
-        let token = Address::generate(&env);
+        let guarantor = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let keeper = Address::generate(&env);
         let nft_contract = env.register_contract(None, MockNft);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        token_admin_client.mint(&guarantor, &3000);
+        token_admin_client.mint(&stranger, &3000);
+        token_admin_client.mint(&user2, &3000);
+
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
+
+        // The guarantor earns a track record by seeing a circle through with someone else
+        let first = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&guarantor, &first);
+        client.join_circle(&user2, &first);
+        for _ in 0..2 {
+            client.deposit(&guarantor, &first, &1000);
+            client.deposit(&user2, &first, &1000);
+            client.distribute_payout(&creator, &first);
+        }
+        let reputation = client.get_reputation(&guarantor, &token);
+        assert_eq!(reputation.circles_completed, 1);
+        assert_eq!(reputation.value_completed, 2000);
+
+        // Per-address records are persistent entries with a long TTL, not instance data
+        let reputation_key = DataKey::Reputation(guarantor.clone(), token.clone());
+        let ttl = env.as_contract(&contract_id, || env.storage().persistent().get_ttl(&reputation_key));
+        assert_eq!(ttl, HISTORY_TTL);
+        assert!(env.as_contract(&contract_id, || env.storage().persistent().has(&DataKey::MemberCircle(guarantor.clone()))));
+
+        // A circle of one, where the member only pays themselves, earns nothing
+        let solo = client.create_circle(&creator, &1000, &1, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.join_circle(&stranger, &solo);
+        client.deposit(&stranger, &solo, &1000);
+        client.distribute_payout(&creator, &solo);
+        assert_eq!(client.get_reputation(&stranger, &token), Reputation::default());
+
+        let circle_id = client.create_circle(&creator, &1000, &2, &token, &604800, &0, &Some(nft_contract.clone()));
+        client.set_guarantor_threshold(&creator, &circle_id, &2000);
+
+        // Newcomers cannot join alone, nor be vouched for by another newcomer
+        assert!(client.try_join_circle(&user1, &circle_id).is_err());
+        assert!(client.try_join_with_guarantor(&user1, &circle_id, &stranger, &1000).is_err());
+
+        // Having finished its last circle, the guarantor's address can join another
+        client.join_with_guarantor(&user1, &circle_id, &guarantor, &1000);
+        client.join_with_guarantor(&user2, &circle_id, &guarantor, &0);
+        assert_eq!(token_client.balance(&guarantor), 2000);
+
+        // Rejoining keeps the record from the earlier circle
+        assert_eq!(client.get_member(&first, &user2).total_contributed, 2000);
+        assert_eq!(client.get_member(&circle_id, &user2).total_contributed, 0);
+
+        // user1 misses the round; the guarantee covers it instead of the insurance fund
+        client.deposit(&user2, &circle_id, &1000);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 2 * 604800);
+        client.settle_round(&keeper, &circle_id);
+
+        let guarantee = client.get_guarantee(&circle_id, &user1).unwrap();
+        assert_eq!(guarantee.drawn, 1000);
+        assert_eq!(client.get_member(&circle_id, &user1).insurance_debt, 0);
+        assert_eq!(client.get_reputation(&user1, &token).value_defaulted, 1000);
+        let reputation = client.get_reputation(&guarantor, &token);
+        assert_eq!(reputation.guaranteed_defaults, 1);
+        assert_eq!(reputation.value_guaranteed_defaulted, 1000);
+        assert_eq!(reputation.circles_completed, 1);
+
+        // The round record credits the guarantee, not the insurance fund
+        client.distribute_payout(&creator, &circle_id);
+        let record = client.get_round_record(&circle_id, &0).unwrap();
+        assert_eq!(record.contributors, 0b11);
+        assert_eq!(record.guarantee_covered, 0b01);
+        assert_eq!(record.insurance_covered, 0);
+    }
 
-        SoroSusuTrait::init(env.clone(), admin.clone());
-
-        let circle_id = SoroSusuTrait::create_circle(
-            env.clone(),
-            creator.clone(),
-            1000,
-            5,
-            token.clone(),
-            604800,
-            0,
-            nft_contract.clone(),
-        );
-
-        // Join should trigger mint (mocked)
-        SoroSusuTrait::join_circle(env.clone(), user.clone(), circle_id);
-
+    #[test]
+    fn test_recover_slot_by_guardians_or_members() {
+        let env = Env::default();
         env.mock_all_auths();
+        let contract_id = env.register_contract(None, SoroSusu);
+        let client = SoroSusuClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let creator = Address::generate(&env);
+        let user1 = Address::generate(&env);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        let guardian1 = Address::generate(&env);
+        let guardian2 = Address::generate(&env);
+        let new1 = Address::generate(&env);
+        let new2 = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        let nft_contract = env.register_contract(None, MockNft);
+        let nft_client = MockNftClient::new(&env, &nft_contract);
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+        let token_admin_client = token::StellarAssetClient::new(&env, &token);
+        let token_client = token::Client::new(&env, &token);
+        for user in [&user1, &user2, &user3] {
+            token_admin_client.mint(user, &1000);
+        }
 
-        // Verify member is active
-        let member_key = DataKey::Member(user.clone());
-        let member: Member = env.storage().instance().get(&member_key).unwrap();
-        assert!(member.is_active);
-
-        // Eject member should trigger burn (mocked) and set inactive
-        SoroSusuTrait::eject_member(env.clone(), creator.clone(), circle_id, user.clone());
+        client.init(&admin);
+        client.list_token(&admin, &token, &0, &i128::MAX, &i128::MAX);
 
-        let member_after: Member = env.storage().instance().get(&member_key).unwrap();
-        assert!(!member_after.is_active);
+        let circle_id = client.create_circle(&creator, &1000, &3, &token, &604800, &0, &Some(nft_contract.clone()));
+        for user in [&user1, &user2, &user3] {
+            client.join_circle(user, &circle_id);
+            client.deposit(user, &circle_id, &1000);
+        }
 
-        // Inactive member cannot deposit
-        let result = std::panic::catch_unwind(|| {
-            SoroSusuTrait::deposit(env.clone(), user.clone(), circle_id);
-        });
-        assert!(result.is_err());
+        // user1 loses their key; two of their guardians approve moving the slot
+        client.set_recovery_guardians(&user1, &circle_id, &vec![&env, guardian1.clone(), guardian2.clone()], &2);
+        client.recover_slot(&guardian1, &circle_id, &user1, &new1);
+        assert!(client.get_pending_recovery(&circle_id, &user1).is_none());
+        client.recover_slot(&guardian2, &circle_id, &user1, &new1);
+
+        // The move waits out the timelock, and the slot's payout is held meanwhile
+        let pending = client.get_pending_recovery(&circle_id, &user1).unwrap();
+        assert_eq!(pending.new, new1);
+        assert_eq!(client.get_members(&circle_id).get(0).unwrap(), user1);
+        assert!(client.try_execute_recovery(&circle_id, &user1).is_err());
+        client.distribute_payout(&creator, &circle_id);
+        assert_eq!(token_client.balance(&user1), 0);
+        assert_eq!(client.get_claimable(&circle_id, &user1).unwrap().amount, 3000);
+
+        env.ledger().set_timestamp(pending.executable_at);
+        client.execute_recovery(&circle_id, &user1);
+
+        let member = client.get_member(&circle_id, &new1);
+        assert_eq!(member.index, 0);
+        assert_eq!(member.total_contributed, 1000);
+        assert_eq!(client.get_members(&circle_id).get(0).unwrap(), new1);
+        assert!(client.get_recovery_guardians(&circle_id, &new1).is_some());
+        assert_eq!(nft_client.recovered_to(&encode_token_id(circle_id, 0)), Some(new1.clone()));
+        assert!(client.try_get_member(&circle_id, &user1).is_err());
+
+        // The held payout followed the slot
+        assert_eq!(client.claim(&new1, &circle_id), 3000);
+        assert_eq!(token_client.balance(&new1), 3000);
+
+        // Without guardians, a majority of the other members must agree; outsiders cannot vote
+        assert!(client.try_recover_slot(&outsider, &circle_id, &user2, &new2).is_err());
+        client.recover_slot(&new1, &circle_id, &user2, &new2);
+        client.recover_slot(&user3, &circle_id, &user2, &new2);
+        assert!(client.get_pending_recovery(&circle_id, &user2).is_some());
+
+        // user2 still holds their key and cancels a recovery they never asked for
+        assert!(client.try_cancel_recovery(&user3, &circle_id).is_err());
+        client.cancel_recovery(&user2, &circle_id);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 2 * 259200);
+        assert!(client.try_execute_recovery(&circle_id, &user2).is_err());
+        assert_eq!(client.get_members(&circle_id).get(1).unwrap(), user2);
     }
 }